@@ -0,0 +1,275 @@
+//! Configurable text rendering of boards.
+//! The default format is the one used by `Display`, coloured stars that can be parsed back,
+//! other formats can drop the colours for log files, tell players apart by their disc,
+//! number the columns, mark the last move or print the whole board on a single line
+
+use colored::{Color, Colorize};
+
+use crate::player::Player;
+
+use super::{Board, Col, Row};
+
+/// Characters drawn for the pieces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Discs {
+    /// The same `*` for every player, told apart by colour only
+    #[default]
+    Star,
+    /// The letter of the player, `r`, `y`, `g` or `b`, uppercase when marked
+    Letter,
+    /// A different Unicode shape for every player, filled in when marked
+    Unicode,
+}
+
+impl Discs {
+    fn glyph(self, player: Player, marked: bool) -> char {
+        match (self, marked) {
+            (Discs::Star, false) => '*',
+            (Discs::Star, true) => '#',
+            (Discs::Letter, false) => player.letter(),
+            (Discs::Letter, true) => player.letter().to_ascii_uppercase(),
+            (Discs::Unicode, false) => ['○', '◇', '△', '□'][player as usize],
+            (Discs::Unicode, true) => ['●', '◆', '▲', '■'][player as usize],
+        }
+    }
+}
+
+/// Pieces standing out from the others
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    #[default]
+    None,
+    /// The last played piece
+    LastMove,
+    /// Every piece of a line completed by the last move
+    WinningLine,
+}
+
+/// Options for rendering a board as text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoardFormat {
+    /// Colour the pieces with ANSI escape codes
+    pub colour: bool,
+    pub discs: Discs,
+    /// Number the columns from 1 below the board, as in [`Board::from_moves`].
+    /// Only the last digit is printed on boards wider than 9 columns
+    pub coordinates: bool,
+    pub highlight: Highlight,
+    /// Print the rows on a single line, top row first and separated by `/`,
+    /// with `.` for empty cells. Coordinates are left out
+    pub compact: bool,
+}
+
+impl Default for BoardFormat {
+    fn default() -> Self {
+        Self {
+            colour: true,
+            discs: Discs::Star,
+            coordinates: false,
+            highlight: Highlight::None,
+            compact: false,
+        }
+    }
+}
+
+impl BoardFormat {
+    /// Plain letters without any escape code, safe to write to log files
+    pub fn plain() -> Self {
+        Self {
+            colour: false,
+            discs: Discs::Letter,
+            ..Self::default()
+        }
+    }
+
+    fn colour_of(player: Player) -> Color {
+        match player {
+            Player::Red => Color::Red,
+            Player::Yellow => Color::Cyan,
+            Player::Green => Color::Green,
+            Player::Blue => Color::Blue,
+        }
+    }
+
+    /// Cells to highlight on the board, as a mask in the order of [`super::to_position`]
+    fn marked<const W: usize, const H: usize>(&self, board: &Board<W, H>) -> u64 {
+        let cells = match self.highlight {
+            Highlight::None => Vec::new(),
+            Highlight::LastMove => board
+                .history()
+                .last()
+                .map(|&col| vec![(board.height(col) as Row - 1, col)])
+                .unwrap_or_default(),
+            Highlight::WinningLine => board
+                .last_move_winning_lines()
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
+        cells.into_iter().fold(0, |acc, (row, col)| {
+            acc | 1 << super::to_position::<H>(col, row)
+        })
+    }
+
+    fn cell<const W: usize, const H: usize>(
+        &self,
+        board: &Board<W, H>,
+        row: Row,
+        col: Col,
+        marked: u64,
+    ) -> String {
+        let Some(player) = board.piece_at(row, col).0 else {
+            return if self.compact { "." } else { " " }.to_owned();
+        };
+        let marked = marked >> super::to_position::<H>(col, row) & 1 == 1;
+        let glyph = self.discs.glyph(player, marked).to_string();
+        match (self.colour, marked) {
+            (false, _) => glyph,
+            (true, false) => glyph.color(Self::colour_of(player)).to_string(),
+            (true, true) => glyph.color(Self::colour_of(player)).bold().to_string(),
+        }
+    }
+
+    /// Renders the board, a line per row top row first, each line ending with a new line.
+    /// Compact boards are a single line without a new line
+    pub fn render<const W: usize, const H: usize>(&self, board: &Board<W, H>) -> String {
+        let marked = self.marked(board);
+        let rows = (0..H as Row).rev().map(|row| {
+            (0..W as Col)
+                .map(|col| self.cell(board, row, col, marked))
+                .collect::<Vec<_>>()
+        });
+        if self.compact {
+            return rows
+                .map(|cells| cells.concat())
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+
+        let mut out = String::new();
+        for cells in rows {
+            out.push_str(&format!("|{}|\n", cells.join("|")));
+        }
+        if self.coordinates {
+            let numbers = (1..=W)
+                .map(|col| (col % 10).to_string())
+                .collect::<Vec<_>>();
+            out.push_str(&format!(" {} \n", numbers.join(" ")));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::StandardBoard;
+
+    use super::*;
+
+    #[test]
+    fn default_prints_the_pieces() {
+        let board = StandardBoard::from_moves("4453").unwrap();
+        let expected = (0..6)
+            .rev()
+            .map(|row| {
+                let cells = (0..7).map(|col| board.piece_at(row, col).to_string());
+                format!("|{}|\n", cells.collect::<Vec<_>>().join("|"))
+            })
+            .collect::<String>();
+        assert_eq!(BoardFormat::default().render(&board), expected);
+        assert_eq!(board.to_string(), expected);
+    }
+
+    #[test]
+    fn plain_letters() {
+        let board = StandardBoard::from_moves("4453").unwrap();
+        let out = BoardFormat::plain().render(&board);
+        assert_eq!(
+            out,
+            "| | | | | | | |\n\
+             | | | | | | | |\n\
+             | | | | | | | |\n\
+             | | | | | | | |\n\
+             | | | |y| | | |\n\
+             | | |y|r|r| | |\n"
+        );
+        assert!(!out.contains('\u{1b}'));
+        assert_eq!(out.parse::<StandardBoard>(), Ok(board));
+    }
+
+    #[test]
+    fn colours_can_be_turned_off() {
+        colored::control::set_override(true);
+        let board = StandardBoard::from_moves("45").unwrap();
+        let format = BoardFormat {
+            discs: Discs::Unicode,
+            ..BoardFormat::default()
+        };
+        assert!(format.render(&board).contains('\u{1b}'));
+        let plain = BoardFormat {
+            colour: false,
+            ..format
+        }
+        .render(&board);
+        assert!(!plain.contains('\u{1b}'));
+        assert!(plain.ends_with("| | | |○|◇| | |\n"));
+    }
+
+    #[test]
+    fn coordinates_and_compact() {
+        let board = StandardBoard::from_moves("11").unwrap();
+        let format = BoardFormat {
+            coordinates: true,
+            ..BoardFormat::plain()
+        };
+        let out = format.render(&board);
+        assert_eq!(out.lines().count(), 7);
+        assert!(out.ends_with("|r| | | | | | |\n 1 2 3 4 5 6 7 \n"));
+
+        let compact = BoardFormat {
+            compact: true,
+            ..format
+        };
+        assert_eq!(
+            compact.render(&board),
+            "......./......./......./......./y....../r......"
+        );
+        let wide = Board::<12, 2>::default();
+        assert!(format
+            .render(&wide)
+            .ends_with(" 1 2 3 4 5 6 7 8 9 0 1 2 \n"));
+    }
+
+    #[test]
+    fn highlights() {
+        let board = StandardBoard::from_moves("1212121").unwrap();
+        let last = BoardFormat {
+            highlight: Highlight::LastMove,
+            compact: true,
+            ..BoardFormat::plain()
+        };
+        assert_eq!(
+            last.render(&board),
+            "......./......./R....../ry...../ry...../ry....."
+        );
+        let line = BoardFormat {
+            highlight: Highlight::WinningLine,
+            ..last
+        };
+        assert_eq!(
+            line.render(&board),
+            "......./......./R....../Ry...../Ry...../Ry....."
+        );
+        // Nothing to mark without a winning line
+        let board = StandardBoard::from_moves("121212").unwrap();
+        assert!(!line.render(&board).contains(char::is_uppercase));
+        let stars = BoardFormat {
+            discs: Discs::Star,
+            ..last
+        };
+        assert_eq!(
+            stars.render(&board),
+            "......./......./......./*#...../**...../**....."
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! The original `bitvec` implementation of the board, only kept
+//! as a reference for the property tests of the `u64` version
+
+use bitvec::prelude::*;
+use itertools::{EitherOrBoth, Itertools};
+use rand::prelude::*;
+
+use crate::{
+    board::{Col, IllegalMove, Row},
+    player::Player,
+    BOARD_SIZE,
+};
+
+#[inline]
+fn to_position<const H: usize>(col: Col, row: Row) -> usize {
+    col as usize * H + row as usize
+}
+
+#[inline]
+fn get_col<const W: usize, const H: usize>(indx: Col) -> u64 {
+    assert!((indx as usize) < W);
+    (u64::MAX >> (u64::BITS as usize - H)) << (indx as usize * H)
+}
+
+#[inline]
+fn get_row<const W: usize, const H: usize>(indx: Row) -> u64 {
+    assert!((indx as usize) < H);
+    (0..W).fold(0, |acc, col| acc | 1 << (col * H)) << indx
+}
+
+#[inline]
+fn get_main_diag<const W: usize, const H: usize>(indx: i8) -> u64 {
+    assert!(-(H as i8) < indx && indx < W as i8);
+    (0..W as i8)
+        .map(|col| (col, col - indx))
+        .filter(|(_, row)| (0..H as i8).contains(row))
+        .fold(0, |acc, (col, row)| {
+            acc | 1 << to_position::<H>(col as Col, row as Row)
+        })
+}
+
+#[inline]
+fn get_sec_diag<const W: usize, const H: usize>(indx: i8) -> u64 {
+    assert!(-(W as i8) < indx && indx < H as i8);
+    (0..W as i8)
+        .map(|col| (col, indx + W as i8 - 1 - col))
+        .filter(|(_, row)| (0..H as i8).contains(row))
+        .fold(0, |acc, (col, row)| {
+            acc | 1 << to_position::<H>(col as Col, row as Row)
+        })
+}
+
+type BitBoard = BitArr!(for BOARD_SIZE, in Col, Lsb0);
+
+#[derive(Debug, Clone)]
+pub(super) struct LegacyBoard<const W: usize, const H: usize> {
+    red: BitBoard,
+    yellow: BitBoard,
+    win_length: usize,
+}
+
+impl<const W: usize, const H: usize> LegacyBoard<W, H> {
+    pub(super) fn with_win_length(win_length: usize) -> Self {
+        Self {
+            red: BitArray::ZERO,
+            yellow: BitArray::ZERO,
+            win_length,
+        }
+    }
+
+    pub(super) fn from_rng(rng: &mut impl RngCore, win_length: usize) -> Self {
+        let mask: u64 = rng.gen();
+        let mut numb: BitBoard = BitArray::ZERO;
+        for i in 0..W {
+            numb[i * H..(i * H + rng.gen_range(0..=H))].fill(true);
+        }
+        let a = numb & mask.view_bits::<Lsb0>();
+        let b = numb ^ a;
+        Self {
+            red: a,
+            yellow: b,
+            win_length,
+        }
+    }
+
+    fn column_height(&self, col: Col) -> usize {
+        let range = to_position::<H>(col, 0)..to_position::<H>(col + 1, 0);
+        let row_red = self.red[range.clone()].last_one();
+        let row_yellow = self.yellow[range].last_one();
+
+        match (row_red, row_yellow) {
+            (None, None) => 0,
+            (None, Some(e)) => e + 1,
+            (Some(e), None) => e + 1,
+            (Some(a), Some(b)) => a.max(b) + 1,
+        }
+    }
+
+    pub(super) fn play(&mut self, player: Player, col: Col) -> Result<Row, IllegalMove> {
+        if col as usize >= W {
+            return Err(IllegalMove::OutOfBounds);
+        }
+
+        let indx = self.column_height(col);
+
+        if indx >= H {
+            return Err(IllegalMove::StackIsFull);
+        }
+
+        let arr = match player {
+            Player::Red => &mut self.red,
+            Player::Yellow => &mut self.yellow,
+            // The property tests only compare two-player games against this board
+            _ => unreachable!("The legacy board only holds red and yellow pieces, not {player:?}"),
+        };
+
+        arr.set(to_position::<H>(col, indx as Row), true);
+        Ok(indx as Row)
+    }
+
+    pub(super) fn check_win(&self, row: Row, col: Col, player: Player) -> bool {
+        let arr = match player {
+            Player::Red => &self.red,
+            Player::Yellow => &self.yellow,
+            _ => unreachable!("The legacy board only holds red and yellow pieces, not {player:?}"),
+        };
+        let arr_mask = [
+            get_row::<W, H>(row),
+            get_col::<W, H>(col),
+            get_sec_diag::<W, H>((row + col) as i8 - (W as i8 - 1)),
+            get_main_diag::<W, H>(col as i8 - row as i8),
+        ];
+        arr_mask.into_iter().any(|mask| {
+            let mask = mask.view_bits::<Lsb0>();
+            let arr_masked = *arr & mask;
+            mask.iter_ones()
+                .enumerate()
+                .merge_join_by(arr_masked.iter_ones(), |(_indx, el1), el2| el1.cmp(el2))
+                .filter_map(|res| {
+                    if let EitherOrBoth::Both((indx, _), _) = res {
+                        Some(indx)
+                    } else {
+                        None
+                    }
+                })
+                .collect_vec()
+                .windows(self.win_length)
+                .any(|el| {
+                    el.iter()
+                        .copied()
+                        .zip(el[1..].iter().copied())
+                        .all(|(a, b)| a + 1 == b)
+                })
+        })
+    }
+
+    pub(super) fn valid_moves(&self) -> [bool; W] {
+        std::array::from_fn(|col| self.column_height(col as Col) < H)
+    }
+}
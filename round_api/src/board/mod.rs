@@ -0,0 +1,1647 @@
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use crate::{piece::Piece, player::Player, BOARD_SIZE, HEIGHT, MAX_PLAYERS, WIDTH, WIN_LENGTH};
+
+mod format;
+#[cfg(test)]
+mod legacy;
+mod parse;
+mod perft;
+#[cfg(test)]
+mod reference;
+mod serialize;
+mod threats;
+mod validate;
+mod zobrist;
+
+pub use format::{BoardFormat, Discs, Highlight};
+pub use parse::ParseBoardError;
+pub use validate::Unreachable;
+
+pub type Col = u8;
+pub type Row = u8;
+
+/// Illegal move possibilities
+/// Either move is out of bounds, the current column is full,
+/// it is not the turn of the player or the game is already over.
+/// Variants of the game can also refuse a kind of move,
+/// or popping a piece the player does not own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IllegalMove {
+    OutOfBounds,
+    StackIsFull,
+    WrongPlayer,
+    GameOver,
+    NotAllowed,
+    CannotPop,
+}
+
+/// Describes possible outcomes for each play
+/// Either there is an illegal move, a valid play or the game has terminated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamePlay {
+    ValidPlay,
+    InvalidBoard(IllegalMove),
+    GameTerminated(TerminatedStatus),
+}
+
+/// Game terminated status
+/// Either a player had won or a draw occured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminatedStatus {
+    Win(Player),
+    Draw,
+}
+
+/// Bit index of a cell. Columns are stored one after the other,
+/// each one taking `H` bits from the bottom row upwards.
+#[inline]
+pub(crate) const fn to_position<const H: usize>(col: Col, row: Row) -> usize {
+    col as usize * H + row as usize
+}
+
+/// Mask with the `n` lowest bits set
+#[inline]
+const fn low_bits(n: usize) -> u64 {
+    if n >= u64::BITS as usize {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Left shift dropping every bit pushed out of the word
+#[inline]
+const fn shift_left(cells: u64, n: usize) -> u64 {
+    if n >= u64::BITS as usize {
+        0
+    } else {
+        cells << n
+    }
+}
+
+#[inline]
+pub(crate) const fn get_col<const W: usize, const H: usize>(indx: Col) -> u64 {
+    assert!((indx as usize) < W);
+    low_bits(H) << (indx as usize * H)
+}
+
+#[inline]
+const fn get_row<const W: usize, const H: usize>(indx: Row) -> u64 {
+    assert!((indx as usize) < H);
+    let mut mask = 0;
+    let mut col = 0;
+    while col < W {
+        mask |= 1 << to_position::<H>(col as Col, indx);
+        col += 1;
+    }
+    mask
+}
+
+/// Cells on the diagonal going up and to the right, indexed by `col - row`
+#[inline]
+const fn get_main_diag<const W: usize, const H: usize>(indx: i8) -> u64 {
+    assert!(-(H as i8) < indx && indx < W as i8);
+    let mut mask = 0;
+    let mut col = 0;
+    while col < W as i8 {
+        let row = col - indx;
+        if 0 <= row && row < H as i8 {
+            mask |= 1 << to_position::<H>(col as Col, row as Row);
+        }
+        col += 1;
+    }
+    mask
+}
+
+/// Cells on the diagonal going down and to the right, indexed by `row + col - (W - 1)`
+#[inline]
+const fn get_sec_diag<const W: usize, const H: usize>(indx: i8) -> u64 {
+    assert!(-(W as i8) < indx && indx < H as i8);
+    let mut mask = 0;
+    let mut col = 0;
+    while col < W as i8 {
+        let row = indx + W as i8 - 1 - col;
+        if 0 <= row && row < H as i8 {
+            mask |= 1 << to_position::<H>(col as Col, row as Row);
+        }
+        col += 1;
+    }
+    mask
+}
+
+/// Column, row and diagonal masks going through every cell,
+/// in the order of [`Direction::ALL`]
+const fn get_lines<const W: usize, const H: usize>() -> [[u64; 4]; BOARD_SIZE] {
+    let mut lines = [[0; 4]; BOARD_SIZE];
+    let mut col = 0;
+    while col < W {
+        let mut row = 0;
+        while row < H {
+            lines[to_position::<H>(col as Col, row as Row)] = [
+                get_col::<W, H>(col as Col),
+                get_row::<W, H>(row as Row),
+                get_main_diag::<W, H>(col as i8 - row as i8),
+                get_sec_diag::<W, H>((row + col) as i8 - (W as i8 - 1)),
+            ];
+            row += 1;
+        }
+        col += 1;
+    }
+    lines
+}
+
+/// Directions a line can take, each one going towards higher bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Vertical,
+    Horizontal,
+    MainDiag,
+    SecDiag,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Vertical,
+        Direction::Horizontal,
+        Direction::MainDiag,
+        Direction::SecDiag,
+    ];
+}
+
+/// Connect 4 board of `W` columns and `H` rows.
+/// Any size is supported as long as the whole grid fits in the bitboard,
+/// the default parameters give the original 8x8 board.
+/// The number of aligned pieces needed to win is set per board.
+/// Pieces of up to [`MAX_PLAYERS`] players can be stored,
+/// whose turn it is is left to the game being played.
+/// Played columns are kept in a history so moves can be undone,
+/// two boards are equal when they hold the same pieces however they were reached.
+/// A Zobrist key of the position, and of its mirror image, is kept up to date
+/// on every play and undo
+#[derive(Debug, Clone, Copy)]
+pub struct Board<const W: usize = WIDTH, const H: usize = HEIGHT> {
+    /// Cells taken by each player, indexed by [`Player`]
+    pieces: [u64; MAX_PLAYERS],
+    /// Every taken cell
+    mask: u64,
+    win_length: usize,
+    history: [Col; BOARD_SIZE],
+    history_len: usize,
+    key: u64,
+    /// Key of the board flipped left to right
+    mirror_key: u64,
+}
+
+impl<const W: usize, const H: usize> PartialEq for Board<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pieces == other.pieces && self.win_length == other.win_length
+    }
+}
+
+impl<const W: usize, const H: usize> Eq for Board<W, H> {}
+
+impl<const W: usize, const H: usize> Hash for Board<W, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.key);
+    }
+}
+
+/// The classic 7 columns by 6 rows board
+pub type StandardBoard = Board<7, 6>;
+
+impl<const W: usize, const H: usize> Default for Board<W, H> {
+    fn default() -> Self {
+        Self::with_win_length(WIN_LENGTH)
+    }
+}
+
+impl<const W: usize, const H: usize> Board<W, H> {
+    /// Compile time guard for the board dimensions
+    const FITS: () = assert!(
+        W > 0 && H > 0 && W * H <= BOARD_SIZE,
+        "Board does not fit in the bitboard"
+    );
+    const CELLS: u64 = low_bits(W * H);
+    const BOTTOM: u64 = get_row::<W, H>(0);
+    const TOP: u64 = get_row::<W, H>(H as Row - 1);
+    const LINES: &'static [[u64; 4]; BOARD_SIZE] = &get_lines::<W, H>();
+
+    /// Empty board where `win_length` pieces in a row are needed to win
+    ///
+    /// Panics if the line can not fit in the board
+    pub fn with_win_length(win_length: usize) -> Self {
+        let () = Self::FITS;
+        assert!(
+            (1..=W.max(H)).contains(&win_length),
+            "Win length {win_length} does not fit in a {W}x{H} board"
+        );
+        Self {
+            pieces: [0; MAX_PLAYERS],
+            mask: 0,
+            win_length,
+            history: [0; BOARD_SIZE],
+            history_len: 0,
+            key: 0,
+            mirror_key: 0,
+        }
+    }
+
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Cells taken by `player`, one bit per cell in the order of [`to_position`]
+    #[inline]
+    pub(crate) fn pieces(&self, player: Player) -> u64 {
+        self.pieces[player as usize]
+    }
+
+    /// Player owning the cell at the given bit position, if any
+    #[inline]
+    fn owner(&self, position: usize) -> Option<Player> {
+        Player::ALL
+            .into_iter()
+            .find(|player| self.pieces[*player as usize] >> position & 1 == 1)
+    }
+
+    pub(crate) fn get_array(&self) -> [Piece; BOARD_SIZE] {
+        let mut buffer = [Piece(None); BOARD_SIZE];
+        for (ind, piece) in buffer.iter_mut().enumerate().take(W * H) {
+            *piece = Piece(self.owner(ind));
+        }
+        buffer
+    }
+
+    pub fn from_rng(rng: &mut impl RngCore) -> Self {
+        let mask: u64 = rng.gen();
+        let numb = (0..W).fold(0, |acc, i| acc | low_bits(rng.gen_range(0..=H)) << (i * H));
+        let a = numb & mask;
+        let b = numb ^ a;
+        Self::from_pieces(&[a, b], WIN_LENGTH)
+    }
+
+    /// Board holding the given pieces, indexed by [`Player`], without any history.
+    /// Pieces are expected to be stacked from the bottom of each column
+    pub(crate) fn from_pieces(pieces: &[u64], win_length: usize) -> Self {
+        assert!(pieces.len() <= MAX_PLAYERS, "Too many players");
+        let mut board = Self::with_win_length(win_length);
+        for (player, pieces) in pieces.iter().enumerate() {
+            (0..W * H)
+                .filter(|position| pieces >> position & 1 == 1)
+                .for_each(|position| board.toggle_key(player, position));
+            board.pieces[player] = *pieces;
+            board.mask |= pieces;
+        }
+        board
+    }
+
+    /// Adds or removes the piece of the player with index `player` in both keys
+    #[inline]
+    fn toggle_key(&mut self, player: usize, position: usize) {
+        let mirror = to_position::<H>((W - 1 - position / H) as Col, (position % H) as Row);
+        self.key ^= zobrist::KEYS[player][position];
+        self.mirror_key ^= zobrist::KEYS[player][mirror];
+    }
+
+    /// Zobrist key of the position, stable between runs.
+    /// Positions with the same pieces share the same key whatever the move order
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Key shared by the position and its mirror image,
+    /// as Connect 4 is symmetric left to right
+    pub fn canonical_key(&self) -> u64 {
+        self.key.min(self.mirror_key)
+    }
+
+    /// Number of pieces in the column, which is also the row the next piece lands on
+    ///
+    /// Panics if the column is out of bounds
+    #[inline]
+    pub fn height(&self, col: Col) -> usize {
+        (self.mask & get_col::<W, H>(col)).count_ones() as usize
+    }
+
+    /// Number of pieces in every column
+    pub fn heights(&self) -> [usize; W] {
+        std::array::from_fn(|col| self.height(col as Col))
+    }
+
+    /// Piece on the given cell, counting rows from the bottom
+    ///
+    /// Panics if the cell is out of bounds
+    pub fn piece_at(&self, row: Row, col: Col) -> Piece {
+        assert!((col as usize) < W && (row as usize) < H);
+        Piece(self.owner(to_position::<H>(col, row)))
+    }
+
+    /// Number of pieces of `player` on the board
+    pub fn count(&self, player: Player) -> usize {
+        self.pieces[player as usize].count_ones() as usize
+    }
+
+    /// Number of pieces on the board, whoever they belong to
+    pub fn len(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0
+    }
+
+    /// Whether every column is full, so no piece can be dropped
+    pub fn is_full(&self) -> bool {
+        self.len() == W * H
+    }
+
+    /// Every piece on the board with its row and column,
+    /// column after column from the bottom up
+    pub fn cells(&self) -> impl Iterator<Item = (Row, Col, Player)> + '_ {
+        (0..W as Col).flat_map(move |col| {
+            (0..self.height(col) as Row).map(move |row| {
+                let player = self
+                    .owner(to_position::<H>(col, row))
+                    .expect("Pieces are stacked from the bottom");
+                (row, col, player)
+            })
+        })
+    }
+
+    pub fn play(&mut self, player: Player, col: Col) -> Result<Row, IllegalMove> {
+        if col as usize >= W {
+            return Err(IllegalMove::OutOfBounds);
+        }
+
+        let indx = self.height(col);
+
+        if indx >= H {
+            return Err(IllegalMove::StackIsFull);
+        }
+
+        let position = to_position::<H>(col, indx as Row);
+        self.pieces[player as usize] |= 1 << position;
+        self.mask |= 1 << position;
+        self.toggle_key(player as usize, position);
+        self.history[self.history_len] = col;
+        self.history_len += 1;
+        Ok(indx as Row)
+    }
+
+    /// Removes the last played piece and returns its column.
+    /// Returns `None` when there is no move left to undo
+    pub fn undo(&mut self) -> Option<Col> {
+        self.history_len = self.history_len.checked_sub(1)?;
+        let col = self.history[self.history_len];
+        let position = to_position::<H>(col, self.height(col) as Row - 1);
+        let player = self
+            .owner(position)
+            .expect("Top of a played column can not be empty") as usize;
+        self.pieces[player] &= !(1 << position);
+        self.mask &= !(1 << position);
+        self.toggle_key(player, position);
+        Some(col)
+    }
+
+    /// Removes the bottom piece of the column, which has to belong to `player`,
+    /// and lets the pieces above it fall down by one row.
+    /// Pops can not be undone, so the history is cleared
+    pub fn pop(&mut self, player: Player, col: Col) -> Result<(), IllegalMove> {
+        if col as usize >= W {
+            return Err(IllegalMove::OutOfBounds);
+        }
+        let bottom = 1 << to_position::<H>(col, 0);
+        if self.pieces[player as usize] & bottom == 0 {
+            return Err(IllegalMove::CannotPop);
+        }
+
+        let column = get_col::<W, H>(col);
+        for (player, pieces) in self.pieces.into_iter().enumerate() {
+            let before = pieces & column;
+            let after = (before & !bottom) >> 1;
+            for position in (0..W * H).filter(|position| (before ^ after) >> position & 1 == 1) {
+                self.toggle_key(player, position);
+            }
+            self.pieces[player] = pieces & !column | after;
+        }
+        self.mask = self.pieces.iter().fold(0, |mask, pieces| mask | pieces);
+        self.history_len = 0;
+        Ok(())
+    }
+
+    /// Columns played so far, oldest first
+    pub fn history(&self) -> &[Col] {
+        &self.history[..self.history_len]
+    }
+
+    /// Moves every cell one step along `direction`,
+    /// dropping the ones leaving the board
+    #[inline]
+    fn step(cells: u64, direction: Direction) -> u64 {
+        match direction {
+            Direction::Vertical => (cells & !Self::TOP) << 1,
+            Direction::Horizontal => shift_left(cells, H) & Self::CELLS,
+            Direction::MainDiag => shift_left(cells & !Self::TOP, H + 1) & Self::CELLS,
+            Direction::SecDiag => shift_left(cells & !Self::BOTTOM, H - 1) & Self::CELLS,
+        }
+    }
+
+    /// Moves every cell one step against `direction`,
+    /// dropping the ones leaving the board
+    #[inline]
+    fn step_back(cells: u64, direction: Direction) -> u64 {
+        match direction {
+            Direction::Vertical => (cells & !Self::BOTTOM) >> 1,
+            Direction::Horizontal => cells >> H,
+            Direction::MainDiag => (cells & !Self::BOTTOM) >> (H + 1),
+            Direction::SecDiag => (cells & !Self::TOP) >> (H - 1),
+        }
+    }
+
+    /// Last cell of every line of `win_length` cells along `direction`
+    #[inline]
+    fn aligned(&self, cells: u64, direction: Direction) -> u64 {
+        (1..self.win_length).fold(cells, |acc, _| cells & Self::step(acc, direction))
+    }
+
+    /// Whether `player` has `win_length` aligned pieces on the column,
+    /// the row or one of the diagonals going through the given cell
+    pub fn check_win(&self, row: Row, col: Col, player: Player) -> bool {
+        assert!((col as usize) < W && (row as usize) < H);
+        let pieces = self.pieces[player as usize];
+        Direction::ALL
+            .into_iter()
+            .zip(Self::LINES[to_position::<H>(col, row)])
+            .any(|(direction, line)| self.aligned(pieces, direction) & line != 0)
+    }
+
+    /// Every line of at least `win_length` pieces of `player` going through the given cell,
+    /// at most one per direction as lines are extended as far as they go.
+    /// Each line lists the coordinates of its pieces from one end to the other
+    pub fn winning_lines(&self, row: Row, col: Col, player: Player) -> Vec<Vec<(Row, Col)>> {
+        assert!((col as usize) < W && (row as usize) < H);
+        let pieces = self.pieces[player as usize];
+        let cell = 1 << to_position::<H>(col, row);
+        if pieces & cell == 0 {
+            return Vec::new();
+        }
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| {
+                let mut line = cell;
+                loop {
+                    let grown = line
+                        | pieces & (Self::step(line, direction) | Self::step_back(line, direction));
+                    if grown == line {
+                        break;
+                    }
+                    line = grown;
+                }
+                (line.count_ones() as usize >= self.win_length).then(|| {
+                    // Bits go up along every direction
+                    (0..W * H)
+                        .filter(|position| line >> position & 1 == 1)
+                        .map(|position| ((position % H) as Row, (position / H) as Col))
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    /// Winning lines going through the last played piece, see [`Board::winning_lines`].
+    /// Empty when the last move did not win or there is no history
+    pub fn last_move_winning_lines(&self) -> Vec<Vec<(Row, Col)>> {
+        let Some(&col) = self.history().last() else {
+            return Vec::new();
+        };
+        let row = self.height(col) as Row - 1;
+        let player = self
+            .owner(to_position::<H>(col, row))
+            .expect("Top of a played column can not be empty");
+        self.winning_lines(row, col, player)
+    }
+
+    /// Every line of `win_length` cells fitting in the board, whatever its pieces
+    pub(crate) fn windows(&self) -> impl Iterator<Item = u64> + '_ {
+        Direction::ALL.into_iter().flat_map(move |direction| {
+            let ends = self.aligned(Self::CELLS, direction);
+            (0..W * H)
+                .filter(move |position| ends >> position & 1 == 1)
+                .map(move |position| {
+                    (1..self.win_length).fold(1 << position, |window, _| {
+                        window | Self::step_back(window, direction)
+                    })
+                })
+        })
+    }
+
+    /// Whether `player` has `win_length` aligned pieces anywhere on the board
+    pub(crate) fn has_won(&self, player: Player) -> bool {
+        let pieces = self.pieces[player as usize];
+        Direction::ALL
+            .into_iter()
+            .any(|direction| self.aligned(pieces, direction) != 0)
+    }
+
+    pub fn valid_moves(&self) -> [bool; W] {
+        self.heights().map(|height| height < H)
+    }
+}
+
+impl<const W: usize, const H: usize> Display for Board<W, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", BoardFormat::default().render(self))
+    }
+}
+
+#[cfg(test)]
+mod board_tests {
+    use crate::{board::to_position, piece::Piece, player::Player, HEIGHT, WIDTH};
+    use rand::prelude::*;
+
+    type Board = crate::board::Board<WIDTH, HEIGHT>;
+
+    #[test]
+    fn create_empty_board_array() {
+        let board = Board::default();
+        assert!(board.get_array().iter().all(|el| el.0.is_none()))
+    }
+
+    mod display_board {
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn test_display_random() {
+            let mut rng = StdRng::seed_from_u64(53);
+            let board = Board::from_rng(&mut rng);
+            println!("{}", board);
+        }
+
+        #[test]
+        fn check_from_string() {
+            let board = Board::default();
+            println!("{}", board)
+        }
+    }
+
+    mod indexing_functions {
+        use crate::board::{get_col, get_main_diag, get_row, get_sec_diag};
+
+        const COL_BASE: u64 = 255;
+        const ROW_BASE: u64 = 72_340_172_838_076_673;
+        const MAIN_DIAG_BASE: u64 = 9_241_421_688_590_303_745;
+        const SEC_DIAG_BASE: u64 = 72_624_976_668_147_840;
+
+        #[test]
+        fn column_testing() {
+            // First column
+            assert_eq!(COL_BASE, get_col::<8, 8>(0));
+            // Second column
+            assert_eq!(65_280, get_col::<8, 8>(1));
+            // Third column
+            assert_eq!(16_711_680, get_col::<8, 8>(2));
+            // Fourth column
+            assert_eq!(4_278_190_080, get_col::<8, 8>(3));
+            // Fifth column
+            assert_eq!(1_095_216_660_480, get_col::<8, 8>(4));
+            // Sixth column
+            assert_eq!(280_375_465_082_880, get_col::<8, 8>(5));
+            // Seventh column
+            assert_eq!(71_776_119_061_217_280, get_col::<8, 8>(6));
+            // 8th column
+            assert_eq!(18_374_686_479_671_623_680, get_col::<8, 8>(7));
+        }
+
+        #[test]
+        fn row_testing() {
+            // First row
+            assert_eq!(ROW_BASE, get_row::<8, 8>(0));
+            // Second row
+            assert_eq!(144_680_345_676_153_346, get_row::<8, 8>(1));
+            // Third row
+            assert_eq!(289_360_691_352_306_692, get_row::<8, 8>(2));
+            // Fourth row
+            assert_eq!(578_721_382_704_613_384, get_row::<8, 8>(3));
+            // Fifth row
+            assert_eq!(1_157_442_765_409_226_768, get_row::<8, 8>(4));
+            // Sixth row
+            assert_eq!(2_314_885_530_818_453_536, get_row::<8, 8>(5));
+            // Seventh row
+            assert_eq!(4_629_771_061_636_907_072, get_row::<8, 8>(6));
+            // Eighth row
+            assert_eq!(9_259_542_123_273_814_144, get_row::<8, 8>(7));
+        }
+
+        #[test]
+        fn main_diag_testing() {
+            // Firso row
+            assert_eq!(MAIN_DIAG_BASE, get_main_diag::<8, 8>(0));
+            // Second row
+            assert_eq!(36_099_303_471_055_874, get_main_diag::<8, 8>(-1));
+            // Third row
+            assert_eq!(141_012_904_183_812, get_main_diag::<8, 8>(-2));
+            // Fourth row
+            assert_eq!(550_831_656_968, get_main_diag::<8, 8>(-3));
+            // Fifth row
+            assert_eq!(2_151_686_160, get_main_diag::<8, 8>(-4));
+            // Sixth row
+            assert_eq!(8_405_024, get_main_diag::<8, 8>(-5));
+            // Seventh row
+            assert_eq!(32_832, get_main_diag::<8, 8>(-6));
+            // Eighth row
+            assert_eq!(128, get_main_diag::<8, 8>(-7));
+
+            // Other halve of diag
+
+            // First row
+            assert_eq!(4_620_710_844_295_151_872, get_main_diag::<8, 8>(1));
+            // Third row
+            assert_eq!(2_310_355_422_147_575_808, get_main_diag::<8, 8>(2));
+            // Fourth row
+            assert_eq!(1_155_177_711_073_755_136, get_main_diag::<8, 8>(3));
+            // Fifth row
+            assert_eq!(577_588_855_528_488_960, get_main_diag::<8, 8>(4));
+            // Sixth row
+            assert_eq!(288_794_425_616_760_832, get_main_diag::<8, 8>(5));
+            // Seventh row
+            assert_eq!(144_396_663_052_566_528, get_main_diag::<8, 8>(6));
+            // Eighth row
+            assert_eq!(72_057_594_037_927_936, get_main_diag::<8, 8>(7));
+        }
+
+        #[test]
+        fn sec_diag_testing() {
+            // Firso row
+            assert_eq!(SEC_DIAG_BASE, get_sec_diag::<8, 8>(0));
+            // Second row
+            assert_eq!(283_691_315_109_952, get_sec_diag::<8, 8>(-1));
+            // Third row
+            assert_eq!(1_108_169_199_648, get_sec_diag::<8, 8>(-2));
+            // Fourth row
+            assert_eq!(4_328_785_936, get_sec_diag::<8, 8>(-3));
+            // Fifth row
+            assert_eq!(16_909_320, get_sec_diag::<8, 8>(-4));
+            // Sixth row
+            assert_eq!(66_052, get_sec_diag::<8, 8>(-5));
+            // Seventh row
+            assert_eq!(258, get_sec_diag::<8, 8>(-6));
+            // Eighth row
+            assert_eq!(1, get_sec_diag::<8, 8>(-7));
+
+            // Other halve of diag
+
+            // Second row
+            assert_eq!(145_249_953_336_295_424, get_sec_diag::<8, 8>(1));
+            // Third row
+            assert_eq!(290_499_906_672_525_312, get_sec_diag::<8, 8>(2));
+            // Fourth row
+            assert_eq!(580_999_813_328_273_408, get_sec_diag::<8, 8>(3));
+            // Fifth row
+            assert_eq!(1_161_999_622_361_579_520, get_sec_diag::<8, 8>(4));
+            // Sixth row
+            assert_eq!(2_323_998_145_211_531_264, get_sec_diag::<8, 8>(5));
+            // Seventh row
+            assert_eq!(4_647_714_815_446_351_872, get_sec_diag::<8, 8>(6));
+            // Eighth row
+            assert_eq!(9_223_372_036_854_775_808, get_sec_diag::<8, 8>(7));
+        }
+    }
+
+    mod play_testing {
+        use crate::{board::IllegalMove, player::Player};
+
+        use super::*;
+        use proptest::prelude::*;
+
+        #[test]
+        fn play_empty_board() {
+            let mut board = Board::default();
+            let mut rng = thread_rng();
+
+            assert_eq!(
+                board
+                    .play(Player::Red, rng.gen_range(0..=7))
+                    .expect("Should work for empty boards"),
+                0
+            );
+        }
+
+        proptest! {
+            #[test]
+            fn play_on_empty_board(a in any::<u8>()) {
+                let mut board = Board::default();
+                let res = board.play(Player::Red, a);
+                if a < 8 {
+                    prop_assert_eq!(res.unwrap(), 0)
+                } else {
+                    prop_assert_eq!(res.unwrap_err(),IllegalMove::OutOfBounds)
+                }
+            }
+        }
+
+        mod against_custom_boards {
+            use super::*;
+
+            mod seed_31 {
+                use super::*;
+                #[test]
+                #[ignore = "Just for printing"]
+                fn print_board() {
+                    let mut rng = StdRng::seed_from_u64(31);
+                    let board = Board::from_rng(&mut rng);
+
+                    println!("31");
+                    println!("{board}")
+                }
+
+                #[test]
+                fn test_col_0() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(31));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(0, 5)] = Piece(Some(Player::Red));
+                    let row = board.play(Player::Red, 0).unwrap();
+                    assert_eq!(5, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_1() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(31));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(1, 1)] = Piece(Some(Player::Red));
+                    let row = board.play(Player::Red, 1).unwrap();
+                    assert_eq!(1, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_4() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(31));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(4, 0)] = Piece(Some(Player::Red));
+                    let row = board.play(Player::Red, 4).unwrap();
+                    assert_eq!(0, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_5() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(31));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(5, 7)] = Piece(Some(Player::Red));
+                    let row = board.play(Player::Red, 5).unwrap();
+                    assert_eq!(7, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+            }
+
+            mod seed_32 {
+                use super::*;
+                #[test]
+                #[ignore = "Just for printing"]
+                fn print_board() {
+                    let mut rng = StdRng::seed_from_u64(32);
+                    let board = Board::from_rng(&mut rng);
+
+                    println!("32");
+                    println!("{board}");
+                }
+
+                #[test]
+                fn test_col_0() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(32));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(0, 0)] = Piece(Some(Player::Yellow));
+                    let row = board.play(Player::Yellow, 0).unwrap();
+                    assert_eq!(0, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_2() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(32));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(2, 5)] = Piece(Some(Player::Yellow));
+                    let row = board.play(Player::Yellow, 2).unwrap();
+                    assert_eq!(5, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_3() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(32));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(3, 0)] = Piece(Some(Player::Yellow));
+                    let row = board.play(Player::Yellow, 3).unwrap();
+                    assert_eq!(0, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_5() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(32));
+                    let mut original_array = board.get_array();
+                    original_array[to_position::<HEIGHT>(5, 6)] = Piece(Some(Player::Yellow));
+                    let row = board.play(Player::Yellow, 5).unwrap();
+                    assert_eq!(6, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+            }
+
+            mod seed_231 {
+
+                use crate::board::IllegalMove;
+
+                use super::*;
+                #[test]
+                #[ignore = "Just for printing"]
+                fn print_board() {
+                    let mut rng = StdRng::seed_from_u64(231);
+                    let board = Board::from_rng(&mut rng);
+
+                    println!("231");
+                    println!("{board}")
+                }
+
+                #[test]
+                fn test_col_0() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(231));
+                    let mut original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    original_array[to_position::<HEIGHT>(0, 1)] = Piece(Some(player));
+                    let row = board.play(player, 0).unwrap();
+                    assert_eq!(1, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_2() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(231));
+                    let mut original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    original_array[to_position::<HEIGHT>(2, 1)] = Piece(Some(player));
+                    let row = board.play(player, 2).unwrap();
+                    assert_eq!(1, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_3() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(231));
+                    let arr = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    let err = board.play(player, 3).unwrap_err();
+                    assert_eq!(IllegalMove::StackIsFull, err);
+                    // Should not affect stack
+                    assert!(arr
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(a, b)| a == b));
+                }
+
+                #[test]
+                fn test_col_6() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(231));
+                    let mut original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    original_array[to_position::<HEIGHT>(6, 0)] = Piece(Some(player));
+                    let row = board.play(player, 6).unwrap();
+                    assert_eq!(0, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+            }
+
+            mod seed_122 {
+
+                use crate::board::IllegalMove;
+
+                use super::*;
+                #[test]
+                #[ignore = "Just for printing"]
+                fn print_board() {
+                    let mut rng = StdRng::seed_from_u64(122);
+                    let board = Board::from_rng(&mut rng);
+
+                    println!("122");
+                    println!("{board}")
+                }
+
+                #[test]
+                fn test_col_0() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(122));
+                    let original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    let row = board.play(player, 0).unwrap_err();
+                    assert_eq!(IllegalMove::StackIsFull, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_1() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(122));
+                    let original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    let row = board.play(player, 1).unwrap_err();
+                    assert_eq!(IllegalMove::StackIsFull, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_5() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(122));
+                    let original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    let row = board.play(player, 5).unwrap_err();
+                    assert_eq!(IllegalMove::StackIsFull, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+
+                #[test]
+                fn test_col_7() {
+                    let mut board = Board::from_rng(&mut StdRng::seed_from_u64(122));
+                    let mut original_array = board.get_array();
+                    let player: Player = thread_rng().gen();
+                    original_array[to_position::<HEIGHT>(7, 0)] = Piece(Some(player));
+                    let row = board.play(player, 7).unwrap();
+                    assert_eq!(0, row);
+                    assert!(original_array
+                        .iter()
+                        .zip(board.get_array().iter())
+                        .all(|(el_org, el_after)| el_org == el_after));
+                }
+            }
+        }
+    }
+
+    mod validate_board {
+        use super::*;
+
+        mod test_vertical {
+            use super::*;
+
+            #[test]
+            fn test_vertical_seed_122() {
+                let board = Board::from_rng(&mut StdRng::seed_from_u64(122));
+
+                for i in 3..=6 {
+                    assert!(board.check_win(i, 5, Player::Yellow));
+                    assert!(!board.check_win(i, 5, Player::Red));
+                }
+            }
+        }
+
+        mod test_diag {
+            use super::*;
+
+            #[test]
+            fn test_diag_seed_124() {
+                let board = Board::from_rng(&mut StdRng::seed_from_u64(124));
+                for (j, i) in [(6, 0), (4, 2), (3, 3)] {
+                    assert!(board.check_win(i, j, Player::Yellow), "for row {i} col {j}");
+                    assert!(!board.check_win(i, j, Player::Red));
+                }
+            }
+        }
+
+        mod test_horizontal {
+            use super::*;
+
+            #[test]
+            fn test_horizontal_seed_123() {
+                let board = Board::from_rng(&mut StdRng::seed_from_u64(123));
+                for i in 4..=7 {
+                    assert!(board.check_win(0, i, Player::Yellow));
+                    assert!(!board.check_win(0, i, Player::Red));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "Testing the interaction between bit representation and array representation"]
+    fn test_array() {
+        for i in 20..50 {
+            let mut rng = StdRng::seed_from_u64(i);
+            let board = Board::from_rng(&mut rng);
+            println!("State:{i}\n{board}");
+        }
+    }
+
+    mod check_valid_moves {
+
+        use super::*;
+
+        #[test]
+        fn test_state_41() {
+            let board = Board::from_rng(&mut StdRng::seed_from_u64(41));
+            let arr = board.valid_moves();
+            let mut arr_valid = [true; 8];
+            arr_valid[2] = false;
+            arr_valid[6] = false;
+            arr_valid[7] = false;
+            assert_eq!(arr, arr_valid);
+        }
+
+        #[test]
+        fn test_state_43() {
+            let board = Board::from_rng(&mut StdRng::seed_from_u64(43));
+            let arr = board.valid_moves();
+            assert_eq!(arr, [true; 8]);
+        }
+
+        #[test]
+        fn test_state_44() {
+            let board = Board::from_rng(&mut StdRng::seed_from_u64(48));
+            let arr = board.valid_moves();
+            let mut arr_valid = [true; 8];
+            arr_valid[3] = false;
+            assert_eq!(arr, arr_valid);
+        }
+    }
+
+    mod other_sizes {
+        use crate::board::{Board, IllegalMove, StandardBoard};
+        use crate::player::Player;
+
+        #[test]
+        fn standard_board_bounds() {
+            let mut board = StandardBoard::default();
+            assert_eq!(board.play(Player::Red, 7), Err(IllegalMove::OutOfBounds));
+            for row in 0..6 {
+                assert_eq!(board.play(Player::Red, 6), Ok(row));
+            }
+            assert_eq!(board.play(Player::Red, 6), Err(IllegalMove::StackIsFull));
+            let mut valid = [true; 7];
+            valid[6] = false;
+            assert_eq!(board.valid_moves(), valid);
+        }
+
+        #[test]
+        fn standard_board_display() {
+            let mut board = StandardBoard::default();
+            board.play(Player::Red, 0).unwrap();
+            let out = board.to_string();
+            assert_eq!(out.lines().count(), 6);
+            assert!(out.lines().all(|line| line.matches('|').count() == 8));
+        }
+
+        #[test]
+        fn standard_board_wins() {
+            let mut board = StandardBoard::default();
+            for col in 3..7 {
+                board.play(Player::Yellow, col).unwrap();
+            }
+            for col in 3..7 {
+                assert!(board.check_win(0, col, Player::Yellow));
+                assert!(!board.check_win(0, col, Player::Red));
+            }
+
+            let mut board = StandardBoard::default();
+            for _ in 0..4 {
+                board.play(Player::Red, 6).unwrap();
+            }
+            assert!(board.check_win(3, 6, Player::Red));
+        }
+
+        #[test]
+        fn main_diagonal_win() {
+            let mut board = Board::<9, 7>::default();
+            for col in 5..9 {
+                for _ in 5..col {
+                    board.play(Player::Yellow, col).unwrap();
+                }
+                let row = board.play(Player::Red, col).unwrap();
+                assert_eq!(row as usize, col as usize - 5);
+            }
+            for (row, col) in [(0, 5), (1, 6), (2, 7), (3, 8)] {
+                assert!(board.check_win(row, col, Player::Red));
+            }
+            assert!(!board.check_win(1, 7, Player::Yellow));
+        }
+    }
+
+    mod queries {
+        use crate::board::{Board, StandardBoard};
+        use crate::piece::Piece;
+        use crate::player::Player;
+        use rand::prelude::*;
+
+        #[test]
+        fn pieces_and_heights() {
+            let board = StandardBoard::from_moves("44453").unwrap();
+            assert_eq!(board.piece_at(0, 3), Piece(Some(Player::Red)));
+            assert_eq!(board.piece_at(1, 3), Piece(Some(Player::Yellow)));
+            assert_eq!(board.piece_at(0, 2), Piece(Some(Player::Red)));
+            assert_eq!(board.piece_at(3, 3), Piece(None));
+            assert_eq!(board.heights(), [0, 0, 1, 3, 1, 0, 0]);
+            assert_eq!(board.height(3), 3);
+            assert_eq!(board.count(Player::Red), 3);
+            assert_eq!(board.count(Player::Yellow), 2);
+            assert_eq!(board.count(Player::Green), 0);
+            assert_eq!(board.len(), 5);
+            assert_eq!(
+                board.cells().collect::<Vec<_>>(),
+                vec![
+                    (0, 2, Player::Red),
+                    (0, 3, Player::Red),
+                    (1, 3, Player::Yellow),
+                    (2, 3, Player::Red),
+                    (0, 4, Player::Yellow),
+                ]
+            );
+        }
+
+        #[test]
+        fn empty_and_full() {
+            let mut board = Board::<2, 2>::with_win_length(2);
+            assert!(board.is_empty());
+            assert!(!board.is_full());
+            assert_eq!(board.cells().count(), 0);
+            for col in [0, 1, 1, 0] {
+                board.play(Player::Red, col).unwrap();
+                assert!(!board.is_empty());
+            }
+            assert!(board.is_full());
+            assert_eq!(board.valid_moves(), [false; 2]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn piece_out_of_bounds() {
+            StandardBoard::default().piece_at(6, 0);
+        }
+
+        #[test]
+        fn queries_match_the_array() {
+            let mut rng = StdRng::seed_from_u64(17);
+            for _ in 0..20 {
+                let board = Board::<8, 8>::from_rng(&mut rng);
+                let array = board.get_array();
+                for (row, col, player) in board.cells() {
+                    assert_eq!(board.piece_at(row, col), Piece(Some(player)));
+                }
+                assert_eq!(
+                    board.cells().count(),
+                    array.iter().filter(|piece| piece.0.is_some()).count()
+                );
+                for player in [Player::Red, Player::Yellow] {
+                    assert_eq!(
+                        board.count(player),
+                        array.iter().filter(|piece| piece.0 == Some(player)).count()
+                    );
+                }
+                for (col, height) in board.heights().into_iter().enumerate() {
+                    assert_eq!(board.valid_moves()[col], height < 8);
+                }
+            }
+        }
+    }
+
+    mod more_players {
+        use crate::board::{Board, IllegalMove};
+        use crate::player::Player;
+
+        #[test]
+        fn green_and_blue_pieces() {
+            let mut board: Board<9, 6> = "
+                | | |b| | | | | | |
+                | |g|b|y| | | | | |
+                |r|g|g|g| | | | | |
+                "
+            .parse()
+            .unwrap();
+            assert_eq!(board.play(Player::Green, 4), Ok(0));
+            assert!(board.check_win(0, 4, Player::Green));
+            assert!(!board.check_win(0, 0, Player::Red));
+            assert_eq!(
+                board.last_move_winning_lines(),
+                vec![vec![(0, 1), (0, 2), (0, 3), (0, 4)]]
+            );
+            for _ in 0..3 {
+                board.play(Player::Blue, 2).unwrap();
+            }
+            assert_eq!(board.play(Player::Blue, 2), Err(IllegalMove::StackIsFull));
+            assert!(!board.valid_moves()[2]);
+            assert_eq!(board.undo(), Some(2));
+            assert!(board.valid_moves()[2]);
+        }
+
+        #[test]
+        fn coloured_display() {
+            colored::control::set_override(true);
+            let mut board = Board::<4, 4>::default();
+            for (col, player) in Player::ALL.into_iter().enumerate() {
+                board.play(player, col as u8).unwrap();
+            }
+            let out = board.to_string();
+            let bottom = out.lines().last().unwrap();
+            for player in Player::ALL {
+                assert!(bottom.contains(&player.to_string()));
+            }
+            assert_eq!(out.parse::<Board<4, 4>>(), Ok(board));
+        }
+    }
+
+    mod win_length {
+        use crate::board::{Board, StandardBoard};
+        use crate::player::Player;
+
+        #[test]
+        fn connect_3() {
+            let mut board = StandardBoard::with_win_length(3);
+            assert_eq!(board.win_length(), 3);
+            for col in 0..3 {
+                board.play(Player::Red, col).unwrap();
+            }
+            assert!(board.check_win(0, 2, Player::Red));
+            assert!(!StandardBoard::default().check_win(0, 2, Player::Red));
+        }
+
+        #[test]
+        fn connect_5() {
+            let mut board = Board::<9, 6>::with_win_length(5);
+            for col in 0..4 {
+                board.play(Player::Yellow, col).unwrap();
+            }
+            assert!(!board.check_win(0, 3, Player::Yellow));
+            board.play(Player::Yellow, 4).unwrap();
+            for col in 0..5 {
+                assert!(board.check_win(0, col, Player::Yellow));
+            }
+        }
+
+        #[test]
+        fn connect_6_vertical() {
+            let mut board = StandardBoard::with_win_length(6);
+            for row in 0..6 {
+                board.play(Player::Red, 2).unwrap();
+                assert_eq!(board.check_win(row, 2, Player::Red), row == 5);
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn line_too_long() {
+            StandardBoard::with_win_length(8);
+        }
+    }
+
+    mod winning_lines {
+        use crate::board::{Board, StandardBoard};
+        use crate::player::Player;
+
+        #[test]
+        fn no_line() {
+            let board = StandardBoard::from_moves("4455").unwrap();
+            assert!(board.last_move_winning_lines().is_empty());
+            assert!(board.winning_lines(0, 3, Player::Red).is_empty());
+            assert!(board.winning_lines(0, 0, Player::Red).is_empty());
+            assert!(StandardBoard::default()
+                .last_move_winning_lines()
+                .is_empty());
+        }
+
+        #[test]
+        fn single_lines() {
+            let board = StandardBoard::from_moves("1212121").unwrap();
+            assert_eq!(
+                board.last_move_winning_lines(),
+                vec![vec![(0, 0), (1, 0), (2, 0), (3, 0)]]
+            );
+
+            let board = StandardBoard::from_moves("4455667").unwrap();
+            assert_eq!(
+                board.last_move_winning_lines(),
+                vec![vec![(0, 3), (0, 4), (0, 5), (0, 6)]]
+            );
+            // The line is found from any of its pieces, not only the last one
+            assert_eq!(
+                board.winning_lines(0, 4, Player::Red),
+                board.last_move_winning_lines()
+            );
+            assert!(board.winning_lines(1, 4, Player::Yellow).is_empty());
+        }
+
+        #[test]
+        fn lines_are_extended() {
+            // Filling the gap joins both sides into a line of five
+            let mut board = Board::<9, 6>::default();
+            for col in [0, 1, 3, 4, 2] {
+                board.play(Player::Yellow, col).unwrap();
+            }
+            assert_eq!(
+                board.last_move_winning_lines(),
+                vec![vec![(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)]]
+            );
+        }
+
+        #[test]
+        fn overlapping_lines() {
+            let board: StandardBoard = "
+                | | | |r| | | |
+                | | |r|y| | | |
+                | |r|y|y| | | |
+                |r|r|r|r|y|y|y|
+                "
+            .parse()
+            .unwrap();
+            let lines = board.winning_lines(0, 0, Player::Red);
+            assert_eq!(
+                lines,
+                vec![
+                    vec![(0, 0), (0, 1), (0, 2), (0, 3)],
+                    vec![(0, 0), (1, 1), (2, 2), (3, 3)],
+                ]
+            );
+            assert!(board.check_win(0, 0, Player::Red));
+        }
+    }
+
+    mod pop {
+        use crate::board::{IllegalMove, StandardBoard};
+        use crate::player::Player;
+
+        #[test]
+        fn pieces_fall_down() {
+            let mut board = StandardBoard::from_moves("44454").unwrap();
+            assert_eq!(board.pop(Player::Yellow, 3), Err(IllegalMove::CannotPop));
+            assert_eq!(board.pop(Player::Red, 0), Err(IllegalMove::CannotPop));
+            assert_eq!(board.pop(Player::Red, 7), Err(IllegalMove::OutOfBounds));
+            board.pop(Player::Red, 3).unwrap();
+            let expected: StandardBoard = "
+                | | | |r| | | |
+                | | | |r| | | |
+                | | | |y|y| | |
+                "
+            .parse()
+            .unwrap();
+            assert_eq!(board, expected);
+            assert_eq!(board.key(), expected.key());
+            assert!(board.history().is_empty());
+            assert_eq!(board.play(Player::Yellow, 3), Ok(3));
+        }
+    }
+
+    mod undo {
+        use crate::board::{IllegalMove, StandardBoard};
+        use crate::player::Player;
+        use rand::prelude::*;
+
+        #[test]
+        fn undo_empty_history() {
+            let mut board = StandardBoard::default();
+            assert_eq!(board.undo(), None);
+            assert_eq!(board, StandardBoard::default());
+        }
+
+        #[test]
+        fn undo_restores_previous_state() {
+            let mut board = StandardBoard::default();
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut states = vec![board];
+            let mut player = Player::Red;
+            for _ in 0..30 {
+                let col = rng.gen_range(0..7);
+                if board.play(player, col).is_ok() {
+                    states.push(board);
+                    player = player.opponent();
+                }
+            }
+            assert_eq!(board.history().len(), states.len() - 1);
+
+            states.pop();
+            while let Some(expected) = states.pop() {
+                let col = *board.history().last().unwrap();
+                assert_eq!(board.undo(), Some(col));
+                assert_eq!(board, expected);
+                assert_eq!(board.history(), expected.history());
+            }
+            assert_eq!(board.undo(), None);
+        }
+
+        #[test]
+        fn failed_play_is_not_recorded() {
+            let mut board = StandardBoard::default();
+            for _ in 0..6 {
+                board.play(Player::Red, 0).unwrap();
+            }
+            assert_eq!(board.play(Player::Red, 0), Err(IllegalMove::StackIsFull));
+            assert_eq!(board.play(Player::Red, 9), Err(IllegalMove::OutOfBounds));
+            assert_eq!(board.history(), &[0; 6]);
+        }
+
+        #[test]
+        fn random_board_has_no_history() {
+            let mut board = StandardBoard::from_rng(&mut StdRng::seed_from_u64(3));
+            let before = board;
+            assert_eq!(board.undo(), None);
+            assert_eq!(board, before);
+        }
+    }
+
+    mod against_legacy {
+        use crate::board::{legacy::LegacyBoard, Board, Col, Row};
+        use crate::player::Player;
+        use proptest::prelude::*;
+        use rand::prelude::*;
+
+        fn compare_random_board<const W: usize, const H: usize>(seed: u64) {
+            let board = Board::<W, H>::from_rng(&mut StdRng::seed_from_u64(seed));
+            let legacy =
+                LegacyBoard::<W, H>::from_rng(&mut StdRng::seed_from_u64(seed), board.win_length());
+            assert_eq!(board.valid_moves(), legacy.valid_moves());
+            for (col, row) in (0..W as Col).flat_map(|col| (0..H as Row).map(move |row| (col, row)))
+            {
+                for player in [Player::Red, Player::Yellow] {
+                    assert_eq!(
+                        board.check_win(row, col, player),
+                        legacy.check_win(row, col, player),
+                        "seed {seed} row {row} col {col}"
+                    );
+                }
+            }
+        }
+
+        proptest! {
+            // The legacy implementation is slow, keep the number of cases down
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn random_games(
+                moves in prop::collection::vec((any::<bool>(), 0..8 as Col), 0..60),
+                win_length in 2..=6usize
+            ) {
+                let mut board = Board::<7, 6>::with_win_length(win_length);
+                let mut legacy = LegacyBoard::<7, 6>::with_win_length(win_length);
+                for (is_red, col) in moves {
+                    let player = if is_red { Player::Red } else { Player::Yellow };
+                    let res = board.play(player, col);
+                    prop_assert_eq!(res, legacy.play(player, col));
+                    prop_assert_eq!(board.valid_moves(), legacy.valid_moves());
+                    if let Ok(row) = res {
+                        for player in [Player::Red, Player::Yellow] {
+                            prop_assert_eq!(
+                                board.check_win(row, col, player),
+                                legacy.check_win(row, col, player)
+                            );
+                        }
+                    }
+                }
+            }
+
+            #[test]
+            fn random_boards(seed in any::<u64>()) {
+                compare_random_board::<8, 8>(seed);
+                compare_random_board::<7, 6>(seed);
+                compare_random_board::<9, 7>(seed);
+                compare_random_board::<4, 4>(seed);
+            }
+        }
+    }
+
+    mod hashing {
+        use crate::board::{to_position, Board, StandardBoard};
+        use crate::player::Player;
+        use rand::prelude::*;
+
+        fn play_all<const W: usize, const H: usize>(board: &mut Board<W, H>, moves: &[u8]) {
+            for (ind, col) in moves.iter().enumerate() {
+                let player = if ind % 2 == 0 {
+                    Player::Red
+                } else {
+                    Player::Yellow
+                };
+                board.play(player, *col).unwrap();
+            }
+        }
+
+        #[test]
+        fn empty_board_key() {
+            assert_eq!(StandardBoard::default().key(), 0);
+            assert_eq!(StandardBoard::default().canonical_key(), 0);
+        }
+
+        #[test]
+        fn stable_keys() {
+            let mut board = StandardBoard::default();
+            play_all(&mut board, &[3, 2, 4]);
+            assert_eq!(board.key(), 0xDF39_FAD4_1E43_FA6E);
+        }
+
+        #[test]
+        fn transpositions_share_keys() {
+            let mut a = StandardBoard::default();
+            play_all(&mut a, &[3, 2, 4, 5]);
+            let mut b = StandardBoard::default();
+            play_all(&mut b, &[4, 5, 3, 2]);
+            assert_eq!(a, b);
+            assert_eq!(a.key(), b.key());
+
+            let mut c = StandardBoard::default();
+            play_all(&mut c, &[2, 3, 4, 5]);
+            assert_ne!(a.key(), c.key());
+        }
+
+        #[test]
+        fn mirrored_positions() {
+            let mut a = StandardBoard::default();
+            play_all(&mut a, &[0, 1, 1]);
+            let mut b = StandardBoard::default();
+            play_all(&mut b, &[6, 5, 5]);
+            assert_ne!(a.key(), b.key());
+            assert_eq!(a.canonical_key(), b.canonical_key());
+
+            let mut symmetric = StandardBoard::default();
+            play_all(&mut symmetric, &[3, 3]);
+            assert_eq!(symmetric.canonical_key(), symmetric.key());
+        }
+
+        #[test]
+        fn undo_restores_keys() {
+            let mut board = StandardBoard::default();
+            let mut rng = StdRng::seed_from_u64(11);
+            let mut keys = vec![(board.key(), board.canonical_key())];
+            for _ in 0..40 {
+                let player = if keys.len() % 2 == 1 {
+                    Player::Red
+                } else {
+                    Player::Yellow
+                };
+                if board.play(player, rng.gen_range(0..7)).is_ok() {
+                    keys.push((board.key(), board.canonical_key()));
+                }
+            }
+            keys.pop();
+            while board.undo().is_some() {
+                assert_eq!(Some((board.key(), board.canonical_key())), keys.pop());
+            }
+        }
+
+        #[test]
+        fn random_boards_are_hashed() {
+            for seed in 0..20 {
+                let board = Board::<8, 8>::from_rng(&mut StdRng::seed_from_u64(seed));
+                let mut replayed = Board::<8, 8>::default();
+                for col in 0..8 {
+                    for row in 0..8 {
+                        if let Some(player) = board.get_array()[to_position::<8>(col, row)].0 {
+                            replayed.play(player, col).unwrap();
+                        }
+                    }
+                }
+                assert_eq!(board, replayed);
+                assert_eq!(board.key(), replayed.key());
+                assert_eq!(board.canonical_key(), replayed.canonical_key());
+            }
+        }
+    }
+}
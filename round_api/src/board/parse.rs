@@ -0,0 +1,352 @@
+//! Parsing boards back from the `Display` diagram and from move sequences
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{player::Player, MAX_PLAYERS, WIN_LENGTH};
+
+use super::{to_position, Board, Col, IllegalMove, Row};
+
+/// Reasons a board could not be parsed.
+/// Lines are counted from the top of the diagram, rows from the bottom of the board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// The diagram has more lines than the board has rows
+    TooManyRows { rows: usize },
+    /// A line is not enclosed between `|`
+    MissingBorder { line: usize },
+    /// A line does not have one cell per column
+    WrongWidth { line: usize, width: usize },
+    /// A cell holds something else than a piece or a space
+    UnknownPiece {
+        line: usize,
+        col: Col,
+        piece: String,
+    },
+    /// A piece is standing above an empty cell
+    FloatingPiece { row: Row, col: Col },
+    /// A move is not a column number of the board
+    IllegalColumn { ply: usize, column: char },
+    /// A move is played on a full column
+    ColumnFull { ply: usize, col: Col },
+    /// A move is played after the game was won
+    GameOver { ply: usize },
+    /// The winning line is longer than both sides of the board
+    WinLength { win_length: usize },
+}
+
+impl Display for ParseBoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseBoardError::TooManyRows { rows } => {
+                write!(f, "Diagram has {rows} rows, more than the board height")
+            }
+            ParseBoardError::MissingBorder { line } => {
+                write!(f, "Line {line} is not enclosed between '|'")
+            }
+            ParseBoardError::WrongWidth { line, width } => {
+                write!(f, "Line {line} has {width} cells, not the board width")
+            }
+            ParseBoardError::UnknownPiece { line, col, piece } => {
+                write!(f, "Unknown piece {piece:?} on line {line}, column {col}")
+            }
+            ParseBoardError::FloatingPiece { row, col } => {
+                write!(f, "Piece at row {row}, column {col} is above an empty cell")
+            }
+            ParseBoardError::IllegalColumn { ply, column } => {
+                write!(f, "Move {ply} ({column:?}) is not a column of the board")
+            }
+            ParseBoardError::ColumnFull { ply, col } => {
+                write!(f, "Move {ply} is played on the full column {col}")
+            }
+            ParseBoardError::GameOver { ply } => {
+                write!(f, "Move {ply} is played after the game was won")
+            }
+            ParseBoardError::WinLength { win_length } => {
+                write!(f, "Win length {win_length} does not fit in the board")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+/// Reads a single cell, either a space, the letter of a player
+/// or the coloured disc printed by `Display`
+fn parse_piece(cell: &str) -> Option<Option<Player>> {
+    if cell == " " {
+        return Some(None);
+    }
+    if let Ok(player) = cell.parse() {
+        return Some(Some(player));
+    }
+    // Without colours every player prints the same disc
+    if Player::Red.to_string() == Player::Yellow.to_string() {
+        return None;
+    }
+    Player::ALL
+        .into_iter()
+        .find(|player| player.to_string() == cell)
+        .map(Some)
+}
+
+/// Parses the diagram printed by `Display` for the classic win length,
+/// see [`Board::from_str_with_win_length`]
+impl<const W: usize, const H: usize> FromStr for Board<W, H> {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_win_length(s, WIN_LENGTH)
+    }
+}
+
+impl<const W: usize, const H: usize> Board<W, H> {
+    fn check_win_length(win_length: usize) -> Result<(), ParseBoardError> {
+        if (1..=W.max(H)).contains(&win_length) {
+            Ok(())
+        } else {
+            Err(ParseBoardError::WinLength { win_length })
+        }
+    }
+
+    /// Parses the diagram printed by `Display`, top row first,
+    /// for a game where `win_length` pieces in a row are needed to win.
+    /// Pieces can also be written with the letters `r`, `y`, `g` and `b`.
+    /// Missing top rows are considered empty
+    pub fn from_str_with_win_length(s: &str, win_length: usize) -> Result<Self, ParseBoardError> {
+        Self::check_win_length(win_length)?;
+        let lines = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        if lines.len() > H {
+            return Err(ParseBoardError::TooManyRows { rows: lines.len() });
+        }
+
+        let mut pieces = [0; MAX_PLAYERS];
+        for (line, text) in lines.iter().enumerate() {
+            let row = (lines.len() - 1 - line) as Row;
+            let cells = text
+                .trim()
+                .strip_prefix('|')
+                .and_then(|text| text.strip_suffix('|'))
+                .ok_or(ParseBoardError::MissingBorder { line })?
+                .split('|')
+                .collect::<Vec<_>>();
+            if cells.len() != W {
+                return Err(ParseBoardError::WrongWidth {
+                    line,
+                    width: cells.len(),
+                });
+            }
+            for (col, cell) in cells.into_iter().enumerate() {
+                let piece = parse_piece(cell).ok_or_else(|| ParseBoardError::UnknownPiece {
+                    line,
+                    col: col as Col,
+                    piece: cell.to_owned(),
+                })?;
+                if let Some(player) = piece {
+                    pieces[player as usize] |= 1 << to_position::<H>(col as Col, row);
+                }
+            }
+        }
+
+        let mask = pieces.iter().fold(0, |mask, pieces| mask | pieces);
+        for col in 0..W as Col {
+            for row in 1..H as Row {
+                let taken = |row| mask >> to_position::<H>(col, row) & 1 == 1;
+                if taken(row) && !taken(row - 1) {
+                    return Err(ParseBoardError::FloatingPiece { row, col });
+                }
+            }
+        }
+        Ok(Self::from_pieces(&pieces, win_length))
+    }
+
+    /// Plays a sequence of moves such as `"4453221"`, starting with red.
+    /// Columns are numbered from 1, so only the first 9 columns can be reached
+    pub fn from_moves(moves: &str) -> Result<Self, ParseBoardError> {
+        Self::from_moves_with_win_length(moves, WIN_LENGTH)
+    }
+
+    /// Same as [`Board::from_moves`] for a game where `win_length` pieces
+    /// in a row are needed to win
+    pub fn from_moves_with_win_length(
+        moves: &str,
+        win_length: usize,
+    ) -> Result<Self, ParseBoardError> {
+        Self::check_win_length(win_length)?;
+        let mut board = Self::with_win_length(win_length);
+        let mut player = Player::Red;
+        let mut won = false;
+        for (ply, column) in moves.chars().enumerate() {
+            if won {
+                return Err(ParseBoardError::GameOver { ply });
+            }
+            let col = column
+                .to_digit(10)
+                .and_then(|col| col.checked_sub(1))
+                .ok_or(ParseBoardError::IllegalColumn { ply, column })?
+                as Col;
+            let row = board.play(player, col).map_err(|err| match err {
+                IllegalMove::StackIsFull => ParseBoardError::ColumnFull { ply, col },
+                _ => ParseBoardError::IllegalColumn { ply, column },
+            })?;
+            won = board.check_win(row, col, player);
+            player = player.opponent();
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::StandardBoard;
+    use rand::prelude::*;
+
+    #[test]
+    fn parse_letters() {
+        let board: StandardBoard = "
+            | | | | | | | |
+            | | | |r| | | |
+            | | |y|y| | | |
+            "
+        .parse()
+        .unwrap();
+        let mut expected = StandardBoard::default();
+        expected.play(Player::Yellow, 2).unwrap();
+        expected.play(Player::Yellow, 3).unwrap();
+        expected.play(Player::Red, 3).unwrap();
+        assert_eq!(board, expected);
+        assert_eq!(board.key(), expected.key());
+        assert!(board.history().is_empty());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        // Players can only be told apart by their colour
+        colored::control::set_override(true);
+        for seed in 0..20 {
+            let board = Board::<8, 8>::from_rng(&mut StdRng::seed_from_u64(seed));
+            assert_eq!(board.to_string().parse::<Board<8, 8>>(), Ok(board));
+            let board = StandardBoard::from_rng(&mut StdRng::seed_from_u64(seed));
+            assert_eq!(board.to_string().parse::<StandardBoard>(), Ok(board));
+        }
+    }
+
+    #[test]
+    fn diagram_errors() {
+        let too_high = "| | | | | | | |\n".repeat(7);
+        assert_eq!(
+            too_high.parse::<StandardBoard>(),
+            Err(ParseBoardError::TooManyRows { rows: 7 })
+        );
+        assert_eq!(
+            "| | | | | | | r".parse::<StandardBoard>(),
+            Err(ParseBoardError::MissingBorder { line: 0 })
+        );
+        assert_eq!(
+            "| | | | | | |".parse::<StandardBoard>(),
+            Err(ParseBoardError::WrongWidth { line: 0, width: 6 })
+        );
+        assert_eq!(
+            "| | |x| | | | |".parse::<StandardBoard>(),
+            Err(ParseBoardError::UnknownPiece {
+                line: 0,
+                col: 2,
+                piece: "x".to_owned()
+            })
+        );
+        assert_eq!(
+            "| |r| | | | | |\n| | | | | | |y|".parse::<StandardBoard>(),
+            Err(ParseBoardError::FloatingPiece { row: 1, col: 1 })
+        );
+    }
+
+    #[test]
+    fn parse_moves() {
+        let board = StandardBoard::from_moves("4453221").unwrap();
+        assert_eq!(board.history(), &[3, 3, 4, 2, 1, 1, 0]);
+        let expected: StandardBoard = "
+            | |y| |y| | | |
+            |r|r|y|r|r| | |
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(board, expected);
+        assert_eq!(StandardBoard::from_moves(""), Ok(StandardBoard::default()));
+    }
+
+    #[test]
+    fn move_errors() {
+        assert_eq!(
+            StandardBoard::from_moves("448"),
+            Err(ParseBoardError::IllegalColumn {
+                ply: 2,
+                column: '8'
+            })
+        );
+        assert_eq!(
+            StandardBoard::from_moves("40"),
+            Err(ParseBoardError::IllegalColumn {
+                ply: 1,
+                column: '0'
+            })
+        );
+        assert_eq!(
+            StandardBoard::from_moves("4a"),
+            Err(ParseBoardError::IllegalColumn {
+                ply: 1,
+                column: 'a'
+            })
+        );
+        assert_eq!(
+            StandardBoard::from_moves("1111111"),
+            Err(ParseBoardError::ColumnFull { ply: 6, col: 0 })
+        );
+        assert_eq!(
+            StandardBoard::from_moves("12121212"),
+            Err(ParseBoardError::GameOver { ply: 7 })
+        );
+    }
+
+    #[test]
+    fn other_win_lengths() {
+        assert_eq!(
+            "|r| | |".parse::<Board<3, 2>>(),
+            Err(ParseBoardError::WinLength { win_length: 4 })
+        );
+        assert_eq!(
+            Board::<3, 2>::from_moves("1"),
+            Err(ParseBoardError::WinLength { win_length: 4 })
+        );
+
+        let board = Board::<3, 2>::from_str_with_win_length("|r| | |", 3).unwrap();
+        let mut expected = Board::<3, 2>::with_win_length(3);
+        expected.play(Player::Red, 0).unwrap();
+        assert_eq!(board, expected);
+        assert_eq!(
+            Board::<3, 2>::from_str_with_win_length("|r| | |", 4),
+            Err(ParseBoardError::WinLength { win_length: 4 })
+        );
+
+        // Three in a row end the game early, even on a narrow board
+        assert_eq!(
+            Board::<3, 2>::from_moves_with_win_length("112233", 3),
+            Err(ParseBoardError::GameOver { ply: 5 })
+        );
+        assert_eq!(
+            Board::<3, 2>::from_moves_with_win_length("14", 3),
+            Err(ParseBoardError::IllegalColumn {
+                ply: 1,
+                column: '4'
+            })
+        );
+        let board = StandardBoard::from_moves_with_win_length("11223", 3).unwrap();
+        assert!(board.check_win(0, 2, Player::Red));
+        assert_eq!(
+            StandardBoard::from_moves_with_win_length("112234", 3),
+            Err(ParseBoardError::GameOver { ply: 5 })
+        );
+    }
+}
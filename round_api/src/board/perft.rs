@@ -0,0 +1,142 @@
+//! Move tree enumeration, counting the positions reachable in a number of plies.
+//! Known counts for the classic board check move generation and win detection,
+//! and the time taken gives a stable benchmark of both
+
+use std::collections::HashSet;
+
+use crate::player::Player;
+
+use super::{Board, Col};
+
+impl<const W: usize, const H: usize> Board<W, H> {
+    /// Player to move in a two-player game started by red
+    fn side_to_move(&self) -> Player {
+        if self.count(Player::Red) > self.count(Player::Yellow) {
+            Player::Yellow
+        } else {
+            Player::Red
+        }
+    }
+
+    /// Number of move sequences of exactly `depth` plies from this position,
+    /// players taking turns from the one to move.
+    /// Games ending earlier are not extended, so they are not counted
+    pub fn perft(&self, depth: usize) -> u64 {
+        let player = self.side_to_move();
+        if depth > 0 && self.has_won(player.opponent()) {
+            return 0;
+        }
+        let mut board = *self;
+        board.perft_from(depth, player)
+    }
+
+    fn perft_from(&mut self, depth: usize, player: Player) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut nodes = 0;
+        for col in 0..W as Col {
+            let Ok(row) = self.play(player, col) else {
+                continue;
+            };
+            if depth == 1 {
+                nodes += 1;
+            } else if !self.check_win(row, col, player) {
+                nodes += self.perft_from(depth - 1, player.opponent());
+            }
+            self.undo();
+        }
+        nodes
+    }
+
+    /// Number of distinct positions reached after exactly `depth` plies,
+    /// transpositions being counted once.
+    /// Games ending earlier are not extended, so they are not counted
+    pub fn perft_unique(&self, depth: usize) -> u64 {
+        let mut player = self.side_to_move();
+        let mut layer = HashSet::from([*self]);
+        for _ in 0..depth {
+            layer = layer
+                .iter()
+                .filter(|board| !board.has_won(player.opponent()))
+                .flat_map(|board| {
+                    (0..W as Col).filter_map(move |col| {
+                        let mut board = *board;
+                        board.play(player, col).ok().map(|_| board)
+                    })
+                })
+                .collect();
+            player = player.opponent();
+        }
+        layer.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::board::StandardBoard;
+
+    use super::*;
+
+    /// Distinct positions after each ply on the classic board, OEIS A212693
+    const UNIQUE: [u64; 9] = [1, 7, 49, 238, 1120, 4263, 16422, 54859, 184275];
+
+    #[test]
+    fn sequences_on_the_classic_board() {
+        let board = StandardBoard::default();
+        // Nobody can win before the seventh ply, nor fill a column before the sixth
+        for depth in 0..=6 {
+            assert_eq!(board.perft(depth), 7u64.pow(depth as u32));
+        }
+        // Sequences filling a column with the first six moves can not play it again
+        assert_eq!(board.perft(7), 7u64.pow(7) - 7);
+    }
+
+    #[test]
+    fn unique_positions_on_the_classic_board() {
+        let board = StandardBoard::default();
+        for (depth, expected) in UNIQUE.into_iter().enumerate().take(8) {
+            assert_eq!(board.perft_unique(depth), expected, "depth {depth}");
+        }
+    }
+
+    #[test]
+    fn terminal_positions_stop_the_search() {
+        // Red wins in the first column on the next move
+        let board = StandardBoard::from_moves("121212").unwrap();
+        assert_eq!(board.perft(1), 7);
+        assert_eq!(board.perft(2), 6 * 7);
+        assert_eq!(board.perft_unique(2), 6 * 7);
+
+        let won = StandardBoard::from_moves("1212121").unwrap();
+        assert_eq!(won.perft(0), 1);
+        assert_eq!(won.perft(1), 0);
+        assert_eq!(won.perft_unique(1), 0);
+
+        // A full board has no moves left
+        let mut full = Board::<2, 2>::with_win_length(2);
+        for col in [0, 1] {
+            full.play(Player::Red, col).unwrap();
+            full.play(Player::Yellow, col).unwrap();
+        }
+        assert_eq!(full.perft(1), 0);
+    }
+
+    #[test]
+    #[ignore = "Benchmark, run with --release"]
+    fn perft_speed() {
+        let board = StandardBoard::default();
+        let start = Instant::now();
+        let nodes = board.perft(9);
+        let elapsed = start.elapsed();
+        println!(
+            "perft(9) = {nodes} in {elapsed:?}, {:.0} nodes/s",
+            nodes as f64 / elapsed.as_secs_f64()
+        );
+        let start = Instant::now();
+        assert_eq!(board.perft_unique(8), UNIQUE[8]);
+        println!("perft_unique(8) in {:?}", start.elapsed());
+    }
+}
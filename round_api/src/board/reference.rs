@@ -0,0 +1,183 @@
+//! Naive board storing every cell in nested vectors, written to be obviously right
+//! rather than fast. It is only used to cross-check the bitboard in property tests,
+//! which play the same moves on both boards
+
+use std::fmt::Display;
+
+use proptest::prelude::*;
+
+use crate::{piece::Piece, player::Player, MAX_PLAYERS};
+
+use super::{Board, Col, IllegalMove, Row};
+
+#[derive(Debug, Clone)]
+pub(super) struct ReferenceBoard {
+    /// Cells indexed by column then row, the bottom row first
+    cells: Vec<Vec<Piece>>,
+    win_length: usize,
+}
+
+impl ReferenceBoard {
+    pub(super) fn new(width: usize, height: usize, win_length: usize) -> Self {
+        Self {
+            cells: vec![vec![Piece(None); height]; width],
+            win_length,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn height(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    fn get(&self, row: isize, col: isize) -> Option<Piece> {
+        let column = self.cells.get(usize::try_from(col).ok()?)?;
+        column.get(usize::try_from(row).ok()?).copied()
+    }
+
+    pub(super) fn play(&mut self, player: Player, col: Col) -> Result<Row, IllegalMove> {
+        let column = self
+            .cells
+            .get_mut(col as usize)
+            .ok_or(IllegalMove::OutOfBounds)?;
+        let row = column
+            .iter()
+            .position(|piece| piece.0.is_none())
+            .ok_or(IllegalMove::StackIsFull)?;
+        column[row] = Piece(Some(player));
+        Ok(row as Row)
+    }
+
+    /// Walks the whole column, row and diagonals going through the cell,
+    /// looking for `win_length` pieces of `player` in a row anywhere on them
+    pub(super) fn check_win(&self, row: Row, col: Col, player: Player) -> bool {
+        let (row, col) = (row as isize, col as isize);
+        let reach = self.width().max(self.height()) as isize;
+        [(1, 0), (0, 1), (1, 1), (-1, 1)]
+            .into_iter()
+            .any(|(d_row, d_col)| {
+                let mut run = 0;
+                (-reach..=reach).any(|step| {
+                    match self.get(row + step * d_row, col + step * d_col) {
+                        Some(Piece(Some(piece))) if piece == player => run += 1,
+                        _ => run = 0,
+                    }
+                    run >= self.win_length
+                })
+            })
+    }
+
+    pub(super) fn valid_moves(&self) -> Vec<bool> {
+        self.cells
+            .iter()
+            .map(|column| column.iter().any(|piece| piece.0.is_none()))
+            .collect()
+    }
+
+    pub(super) fn heights(&self) -> Vec<usize> {
+        self.cells
+            .iter()
+            .map(|column| column.iter().filter(|piece| piece.0.is_some()).count())
+            .collect()
+    }
+}
+
+impl Display for ReferenceBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in (0..self.height()).rev() {
+            write!(f, "|")?;
+            for col in 0..self.width() {
+                write!(f, "{}|", self.cells[col][row])?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays the moves on a bitboard and on the reference board, checking after every move
+/// that both boards accept the same moves, see the same wins and print the same diagram
+fn compare<const W: usize, const H: usize>(
+    moves: &[(usize, Col)],
+    players: usize,
+    win_length: usize,
+) -> Result<(), TestCaseError> {
+    let mut board = Board::<W, H>::with_win_length(win_length);
+    let mut reference = ReferenceBoard::new(W, H, win_length);
+    for (player, col) in moves {
+        let player = Player::ALL[player % players];
+        let res = board.play(player, *col);
+        prop_assert_eq!(res, reference.play(player, *col));
+        prop_assert_eq!(board.valid_moves().to_vec(), reference.valid_moves());
+        prop_assert_eq!(board.heights().to_vec(), reference.heights());
+        prop_assert_eq!(board.to_string(), reference.to_string());
+        if let Ok(row) = res {
+            for other in &Player::ALL[..players] {
+                prop_assert_eq!(
+                    board.check_win(row, *col, *other),
+                    reference.check_win(row, *col, *other),
+                    "{} at row {} col {}\n{}",
+                    other,
+                    row,
+                    col,
+                    reference
+                );
+            }
+        }
+    }
+    // Any cell, not only the last one played
+    for col in 0..W as Col {
+        for row in 0..H as Row {
+            prop_assert_eq!(
+                board.piece_at(row, col),
+                reference.cells[col as usize][row as usize]
+            );
+            for player in &Player::ALL[..players] {
+                prop_assert_eq!(
+                    board.check_win(row, col, *player),
+                    reference.check_win(row, col, *player)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn standard_board(
+        moves in prop::collection::vec((any::<usize>(), 0..8 as Col), 0..60),
+        players in 2..=MAX_PLAYERS,
+        win_length in 2..=6usize,
+    ) {
+        compare::<7, 6>(&moves, players, win_length)?;
+    }
+
+    #[test]
+    fn other_sizes(
+        moves in prop::collection::vec((any::<usize>(), 0..10 as Col), 0..100),
+        players in 2..=MAX_PLAYERS,
+        win_length in 2..=4usize,
+    ) {
+        compare::<8, 8>(&moves, players, win_length)?;
+        compare::<9, 7>(&moves, players, win_length)?;
+        compare::<4, 4>(&moves, players, win_length)?;
+        compare::<1, 5>(&moves, players, 1)?;
+    }
+}
+
+#[test]
+fn diagrams_match() {
+    let mut board = Board::<3, 2>::with_win_length(2);
+    let mut reference = ReferenceBoard::new(3, 2, 2);
+    for (player, col) in [(Player::Red, 0), (Player::Yellow, 2), (Player::Green, 0)] {
+        board.play(player, col).unwrap();
+        reference.play(player, col).unwrap();
+    }
+    assert_eq!(board.to_string(), reference.to_string());
+    let top = format!("|{}| | |", Player::Green);
+    assert_eq!(reference.to_string().lines().next(), Some(top.as_str()));
+}
@@ -0,0 +1,282 @@
+//! Serde support for boards.
+//! Human readable formats store every column as a string of player letters from the bottom up,
+//! binary formats store one word per player with a bit per cell, column after column.
+//! Neither depends on how the board is laid out in memory
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{player::Player, MAX_PLAYERS};
+
+use super::{get_col, low_bits, to_position, Board, Col, ParseBoardError, Row};
+
+#[derive(Serialize, Deserialize)]
+struct ReadableBoard {
+    width: usize,
+    height: usize,
+    win_length: usize,
+    columns: Vec<String>,
+    history: Vec<Col>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactBoard {
+    width: u8,
+    height: u8,
+    win_length: u8,
+    red: u64,
+    yellow: u64,
+    green: u64,
+    blue: u64,
+    history: Vec<Col>,
+}
+
+impl<const W: usize, const H: usize> Serialize for Board<W, H> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let columns = (0..W as Col)
+                .map(|col| {
+                    (0..self.height(col) as Row)
+                        .map(|row| {
+                            self.owner(to_position::<H>(col, row))
+                                .expect("Pieces are stacked from the bottom")
+                                .letter()
+                        })
+                        .collect()
+                })
+                .collect();
+            ReadableBoard {
+                width: W,
+                height: H,
+                win_length: self.win_length,
+                columns,
+                history: self.history().to_vec(),
+            }
+            .serialize(serializer)
+        } else {
+            CompactBoard {
+                width: W as u8,
+                height: H as u8,
+                win_length: self.win_length as u8,
+                red: self.pieces[Player::Red as usize],
+                yellow: self.pieces[Player::Yellow as usize],
+                green: self.pieces[Player::Green as usize],
+                blue: self.pieces[Player::Blue as usize],
+                history: self.history().to_vec(),
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de, const W: usize, const H: usize> Deserialize<'de> for Board<W, H> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (size, win_length, pieces, history) = if deserializer.is_human_readable() {
+            let board = ReadableBoard::deserialize(deserializer)?;
+            if board.columns.len() != W {
+                return Err(D::Error::invalid_length(
+                    board.columns.len(),
+                    &"one entry per column",
+                ));
+            }
+            let mut pieces = [0; MAX_PLAYERS];
+            for (col, column) in board.columns.iter().enumerate() {
+                if column.len() > H {
+                    return Err(D::Error::custom(format!("Column {col} is too high")));
+                }
+                for (row, piece) in column.chars().enumerate() {
+                    let player = piece.to_string().parse::<Player>().map_err(|_| {
+                        D::Error::custom(format!("Unknown piece {piece:?} in column {col}"))
+                    })?;
+                    pieces[player as usize] |= 1 << to_position::<H>(col as Col, row as Row);
+                }
+            }
+            (
+                (board.width, board.height),
+                board.win_length,
+                pieces,
+                board.history,
+            )
+        } else {
+            let board = CompactBoard::deserialize(deserializer)?;
+            (
+                (board.width as usize, board.height as usize),
+                board.win_length as usize,
+                [board.red, board.yellow, board.green, board.blue],
+                board.history,
+            )
+        };
+
+        if size != (W, H) {
+            return Err(D::Error::custom(format!(
+                "Expected a {W}x{H} board, found {}x{}",
+                size.0, size.1
+            )));
+        }
+        if !(1..=W.max(H)).contains(&win_length) {
+            return Err(D::Error::custom(format!(
+                "Win length {win_length} does not fit in a {W}x{H} board"
+            )));
+        }
+        let mask = pieces.iter().fold(0, |mask, pieces| mask | pieces);
+        let overlap =
+            pieces.iter().map(|pieces| pieces.count_ones()).sum::<u32>() != mask.count_ones();
+        if overlap || mask & !low_bits(W * H) != 0 {
+            return Err(D::Error::custom("Pieces overlap or lie outside the board"));
+        }
+        for col in 0..W as Col {
+            let column = (mask & get_col::<W, H>(col)) >> to_position::<H>(col, 0);
+            if column & (column + 1) != 0 {
+                let gap = column.trailing_ones();
+                let row = (gap + (column >> gap).trailing_zeros()) as Row;
+                return Err(D::Error::custom(ParseBoardError::FloatingPiece {
+                    row,
+                    col,
+                }));
+            }
+        }
+
+        let mut board = Self::from_pieces(&pieces, win_length);
+        // Every move of the history has to be undoable
+        let mut heights = board.heights();
+        for col in history.iter().rev() {
+            let height = heights
+                .get_mut(*col as usize)
+                .and_then(|height| height.checked_sub(1))
+                .ok_or_else(|| D::Error::custom(format!("History plays on empty column {col}")))?;
+            heights[*col as usize] = height;
+        }
+        board.history[..history.len()].copy_from_slice(&history);
+        board.history_len = history.len();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use crate::{
+        board::{GamePlay, IllegalMove, StandardBoard, TerminatedStatus},
+        piece::Piece,
+        player::Player,
+        state::GameState,
+    };
+
+    use super::*;
+
+    fn round_trip<T>(value: &T)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+        let bytes = bincode::serialize(value).unwrap();
+        assert_eq!(&bincode::deserialize::<T>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn small_types_round_trip() {
+        round_trip(&Player::Red);
+        round_trip(&Player::Yellow);
+        round_trip(&Piece(None));
+        round_trip(&Piece(Some(Player::Yellow)));
+        round_trip(&IllegalMove::StackIsFull);
+        round_trip(&TerminatedStatus::Win(Player::Red));
+        round_trip(&TerminatedStatus::Draw);
+        round_trip(&GamePlay::ValidPlay);
+        round_trip(&GamePlay::InvalidBoard(IllegalMove::WrongPlayer));
+        round_trip(&GamePlay::GameTerminated(TerminatedStatus::Draw));
+    }
+
+    #[test]
+    fn boards_round_trip() {
+        for seed in 0..20 {
+            round_trip(&Board::<8, 8>::from_rng(&mut StdRng::seed_from_u64(seed)));
+            round_trip(&Board::<9, 7>::from_rng(&mut StdRng::seed_from_u64(seed)));
+        }
+        let board = StandardBoard::from_moves("4453221").unwrap();
+        let json: StandardBoard =
+            serde_json::from_str(&serde_json::to_string(&board).unwrap()).unwrap();
+        assert_eq!(json.history(), board.history());
+        assert_eq!(json.key(), board.key());
+        let bytes: StandardBoard =
+            bincode::deserialize(&bincode::serialize(&board).unwrap()).unwrap();
+        assert_eq!(bytes.history(), board.history());
+    }
+
+    #[test]
+    fn more_players_round_trip() {
+        let board: Board<9, 6> = "
+            | |b| | | | | | | |
+            |r|g|y|b| | | | | |
+            "
+        .parse()
+        .unwrap();
+        round_trip(&board);
+        assert_eq!(
+            serde_json::to_string(&board).unwrap(),
+            r#"{"width":9,"height":6,"win_length":4,"columns":["r","gb","y","b","","","","",""],"history":[]}"#
+        );
+    }
+
+    #[test]
+    fn readable_format() {
+        let board = StandardBoard::from_moves("4453").unwrap();
+        assert_eq!(
+            serde_json::to_string(&board).unwrap(),
+            r#"{"width":7,"height":6,"win_length":4,"columns":["","","y","ry","r","",""],"history":[3,3,4,2]}"#
+        );
+    }
+
+    #[test]
+    fn games_round_trip() {
+        let mut state = GameState::<7, 6>::default();
+        for col in [3, 3, 2, 4, 1, 0, 0] {
+            state.apply(state.to_move(), col);
+        }
+        round_trip(&state);
+        let json = serde_json::to_string(&state).unwrap();
+        let mut copy: GameState<7, 6> = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.undo(), state.undo());
+        assert_eq!(copy, state);
+    }
+
+    #[test]
+    fn invalid_boards() {
+        let parse = |json: &str| serde_json::from_str::<StandardBoard>(json);
+        assert!(parse(
+            r#"{"width":8,"height":8,"win_length":4,"columns":["","","","","","","",""],"history":[]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"width":7,"height":6,"win_length":4,"columns":["x","","","","","",""],"history":[]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"width":7,"height":6,"win_length":9,"columns":["","","","","","",""],"history":[]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"width":7,"height":6,"win_length":4,"columns":["r","","","","","",""],"history":[1]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"width":7,"height":6,"win_length":4,"columns":["r","","","","","",""],"history":[0]}"#
+        )
+        .is_ok());
+
+        let floating = CompactBoard {
+            width: 7,
+            height: 6,
+            win_length: 4,
+            red: 0b10,
+            yellow: 0,
+            green: 0,
+            blue: 0,
+            history: vec![],
+        };
+        assert!(
+            bincode::deserialize::<StandardBoard>(&bincode::serialize(&floating).unwrap()).is_err()
+        );
+    }
+}
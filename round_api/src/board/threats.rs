@@ -0,0 +1,170 @@
+//! Threat analysis: empty cells where a piece would complete a line,
+//! and the moves they allow or force.
+//! Results are column masks, one flag per column like [`Board::valid_moves`].
+//! For the odd/even analysis rows are counted from one at the bottom,
+//! so the row with index 0 is odd. The first player wants odd threats
+//! and the second player even ones, as the board fills up from the bottom
+
+use crate::player::Player;
+
+use super::{get_col, get_row, Board, Col, Direction, Row};
+
+impl<const W: usize, const H: usize> Board<W, H> {
+    const ODD_ROWS: u64 = {
+        let mut rows = 0;
+        let mut row = 0;
+        while row < H {
+            rows |= get_row::<W, H>(row as Row);
+            row += 2;
+        }
+        rows
+    };
+
+    /// Empty cells where a piece of `player` would complete a new line
+    pub(crate) fn threat_cells(&self, player: Player) -> u64 {
+        let pieces = self.pieces[player as usize];
+        let lines = Direction::ALL.map(|direction| self.aligned(pieces, direction));
+        let empty = Self::CELLS & !self.mask;
+        (0..W * H)
+            .filter(|position| empty >> position & 1 == 1)
+            .filter(|&position| {
+                let pieces = pieces | 1 << position;
+                Direction::ALL
+                    .into_iter()
+                    .zip(Self::LINES[position])
+                    .zip(lines)
+                    .any(|((direction, line), lines)| {
+                        // Any line that was not there before goes through the new piece
+                        self.aligned(pieces, direction) & !lines & line != 0
+                    })
+            })
+            .fold(0, |acc, position| acc | 1 << position)
+    }
+
+    /// Cell where a piece would land in every column that is not full
+    #[inline]
+    pub(crate) fn playable_cells(&self) -> u64 {
+        (Self::step(self.mask, Direction::Vertical) | Self::BOTTOM) & !self.mask
+    }
+
+    fn columns(cells: u64) -> [bool; W] {
+        std::array::from_fn(|col| cells & get_col::<W, H>(col as Col) != 0)
+    }
+
+    /// Columns where `player` wins by playing now
+    pub fn winning_moves(&self, player: Player) -> [bool; W] {
+        Self::columns(self.threat_cells(player) & self.playable_cells())
+    }
+
+    /// Columns `player` has to play to stop the opponent from winning on the next move.
+    /// More than one column means the opponent can not be stopped
+    pub fn forced_blocks(&self, player: Player) -> [bool; W] {
+        self.winning_moves(player.opponent())
+    }
+
+    /// Columns where a piece of `player` would let the opponent win right above it,
+    /// unless the move wins the game first
+    pub fn moves_under_threat(&self, player: Player) -> [bool; W] {
+        let below_threats =
+            Self::step_back(self.threat_cells(player.opponent()), Direction::Vertical);
+        let winning = self.threat_cells(player);
+        Self::columns(below_threats & self.playable_cells() & !winning)
+    }
+
+    /// Columns holding a threat of `player` on an odd row, counting rows from one
+    pub fn odd_threats(&self, player: Player) -> [bool; W] {
+        Self::columns(self.threat_cells(player) & Self::ODD_ROWS)
+    }
+
+    /// Columns holding a threat of `player` on an even row, counting rows from one
+    pub fn even_threats(&self, player: Player) -> [bool; W] {
+        Self::columns(self.threat_cells(player) & Self::CELLS & !Self::ODD_ROWS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use crate::board::StandardBoard;
+
+    use super::*;
+
+    fn cols<const W: usize>(cols: &[usize]) -> [bool; W] {
+        std::array::from_fn(|col| cols.contains(&col))
+    }
+
+    #[test]
+    fn immediate_wins_and_blocks() {
+        let board = StandardBoard::from_moves("121212").unwrap();
+        assert_eq!(board.winning_moves(Player::Red), cols(&[0]));
+        assert_eq!(board.winning_moves(Player::Yellow), cols(&[1]));
+        assert_eq!(board.forced_blocks(Player::Red), cols(&[1]));
+
+        let board: StandardBoard = "
+            | | | | | | | |
+            | | |y|y| | | |
+            | | |r|r|r| | |
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(board.winning_moves(Player::Red), cols(&[1, 5]));
+        assert_eq!(board.forced_blocks(Player::Yellow), cols(&[1, 5]));
+        assert_eq!(board.winning_moves(Player::Yellow), cols(&[]));
+    }
+
+    #[test]
+    fn playing_under_a_threat() {
+        let board: StandardBoard = "
+            | |r|r|r|y| | |
+            | |y|y|r|y| | |
+            "
+        .parse()
+        .unwrap();
+        // Red threatens the second row of the first column
+        assert_eq!(board.threat_cells(Player::Red), 1 << 1);
+        assert_eq!(board.moves_under_threat(Player::Yellow), cols(&[0]));
+        assert_eq!(board.moves_under_threat(Player::Red), cols(&[]));
+        assert_eq!(board.winning_moves(Player::Red), cols(&[]));
+    }
+
+    #[test]
+    fn threat_parity() {
+        let board: StandardBoard = "
+            | | | | | | | |
+            | | | | | | | |
+            | | | | | | | |
+            | |r|r|r| | | |
+            | |y|y|y|r| | |
+            |r|y|y|r|y| | |
+            "
+        .parse()
+        .unwrap();
+        // Red threatens the third row, yellow the second
+        assert_eq!(board.odd_threats(Player::Red), cols(&[0, 4]));
+        assert_eq!(board.even_threats(Player::Red), cols(&[]));
+        assert_eq!(board.even_threats(Player::Yellow), cols(&[0]));
+        assert_eq!(board.odd_threats(Player::Yellow), cols(&[]));
+        assert_eq!(board.winning_moves(Player::Yellow), cols(&[0]));
+    }
+
+    #[test]
+    fn winning_moves_match_check_win() {
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..200 {
+            let board = StandardBoard::from_rng(&mut rng);
+            for player in [Player::Red, Player::Yellow] {
+                if board.has_won(player) {
+                    continue;
+                }
+                let expected: [bool; 7] = std::array::from_fn(|col| {
+                    let mut board = board;
+                    board
+                        .play(player, col as Col)
+                        .is_ok_and(|row| board.check_win(row, col as Col, player))
+                });
+                assert_eq!(board.winning_moves(player), expected, "{board}");
+            }
+        }
+    }
+}
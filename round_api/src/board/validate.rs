@@ -0,0 +1,357 @@
+//! Checking that a board can be reached in a game,
+//! and drawing random reachable boards
+
+use std::{collections::HashSet, fmt::Display};
+
+use rand::{prelude::*, seq::index};
+
+use crate::{player::Player, WIN_LENGTH};
+
+use super::{to_position, Board, Col, Row};
+
+/// Reasons a board can not be reached in a two-player game started by red
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unreachable {
+    /// Only red and yellow play such a game
+    ExtraPlayer { player: Player },
+    /// Red moves first so it has as many pieces as yellow or one more
+    PieceCountImbalance { red: usize, yellow: usize },
+    /// Both players have a winning line
+    MultipleWinners,
+    /// Pieces were played after `winner` completed a line
+    PlayAfterWin { winner: Player },
+    /// Pieces can not be stacked by players taking turns
+    NoMoveOrder,
+}
+
+impl Display for Unreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unreachable::ExtraPlayer { player } => {
+                write!(f, "{player:?} has pieces in a two-player game")
+            }
+            Unreachable::PieceCountImbalance { red, yellow } => {
+                write!(f, "Red has {red} pieces and yellow {yellow}")
+            }
+            Unreachable::MultipleWinners => write!(f, "Both players have a winning line"),
+            Unreachable::PlayAfterWin { winner } => {
+                write!(f, "Pieces were played after {winner:?} won")
+            }
+            Unreachable::NoMoveOrder => {
+                write!(f, "Pieces can not be stacked by players taking turns")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Unreachable {}
+
+impl<const W: usize, const H: usize> Board<W, H> {
+    /// Checks that the position can be reached in a game started by red,
+    /// reporting the first reason it can not.
+    ///
+    /// Looks for an order in which the pieces could have been played,
+    /// which takes longer on large boards that turn out to be unreachable
+    pub fn validate(&self) -> Result<(), Unreachable> {
+        if let Some(player) = Player::ALL[2..]
+            .iter()
+            .find(|player| self.pieces[**player as usize] != 0)
+        {
+            return Err(Unreachable::ExtraPlayer { player: *player });
+        }
+        let red = self.pieces[Player::Red as usize].count_ones() as usize;
+        let yellow = self.pieces[Player::Yellow as usize].count_ones() as usize;
+        if red != yellow && red != yellow + 1 {
+            return Err(Unreachable::PieceCountImbalance { red, yellow });
+        }
+
+        let last = if red > yellow {
+            Player::Red
+        } else {
+            Player::Yellow
+        };
+        match (self.has_won(Player::Red), self.has_won(Player::Yellow)) {
+            (true, true) => return Err(Unreachable::MultipleWinners),
+            (false, false) => {}
+            (red_won, _) => {
+                let winner = if red_won { Player::Red } else { Player::Yellow };
+                // The winning piece has to complete every line at once
+                let single_move = (0..W as Col)
+                    .filter_map(|col| self.without_top(col, winner))
+                    .any(|board| !board.has_won(winner));
+                if winner != last || !single_move {
+                    return Err(Unreachable::PlayAfterWin { winner });
+                }
+            }
+        }
+
+        if self.unplay(last, &mut HashSet::new()) {
+            Ok(())
+        } else {
+            Err(Unreachable::NoMoveOrder)
+        }
+    }
+
+    /// Board without the top piece of `col`, if it belongs to `player`
+    fn without_top(&self, col: Col, player: Player) -> Option<Self> {
+        let row = self.height(col).checked_sub(1)?;
+        let position = to_position::<H>(col, row as Row);
+        (self.pieces[player as usize] >> position & 1 == 1).then(|| {
+            let mut board = *self;
+            board.pieces[player as usize] &= !(1 << position);
+            board.mask &= !(1 << position);
+            board.toggle_key(player as usize, position);
+            board
+        })
+    }
+
+    /// Whether the pieces can be taken back from the top, starting with `player`
+    /// and taking turns, without ever leaving a winning line on the board.
+    /// Positions known to fail are remembered by their mask
+    fn unplay(&self, player: Player, dead_ends: &mut HashSet<u64>) -> bool {
+        if self.mask == 0 {
+            return true;
+        }
+        if dead_ends.contains(&self.mask) {
+            return false;
+        }
+        let found = (0..W as Col)
+            .filter_map(|col| self.without_top(col, player))
+            .filter(|board| !board.has_won(Player::Red) && !board.has_won(Player::Yellow))
+            .any(|board| board.unplay(player.opponent(), dead_ends));
+        if !found {
+            dead_ends.insert(self.mask);
+        }
+        found
+    }
+
+    /// Uniformly random reachable position holding `ply` pieces.
+    ///
+    /// Boards with the right number of pieces for each player are drawn uniformly
+    /// until one passes [`Board::validate`], so reaching a position late in the game
+    /// can take many attempts
+    pub fn random_reachable(rng: &mut impl RngCore, ply: usize) -> Self {
+        Self::random_reachable_with_win_length(rng, ply, WIN_LENGTH)
+    }
+
+    /// Same as [`Board::random_reachable`] for a game where `win_length` pieces
+    /// in a row are needed to win
+    pub fn random_reachable_with_win_length(
+        rng: &mut impl RngCore,
+        ply: usize,
+        win_length: usize,
+    ) -> Self {
+        assert!(ply <= W * H, "A {W}x{H} board can not hold {ply} pieces");
+        // Number of ways of stacking `n` pieces in the columns from `col` onwards
+        let mut ways = vec![vec![0u64; ply + 1]; W + 1];
+        ways[W][0] = 1;
+        for col in (0..W).rev() {
+            for n in 0..=ply {
+                ways[col][n] = (0..=H.min(n)).map(|height| ways[col + 1][n - height]).sum();
+            }
+        }
+
+        loop {
+            let mut cells = Vec::with_capacity(ply);
+            let mut left = ply;
+            for col in 0..W {
+                let mut pick = rng.gen_range(0..ways[col][left]);
+                let height = (0..=H.min(left))
+                    .find(|height| {
+                        let count = ways[col + 1][left - height];
+                        pick < count || {
+                            pick -= count;
+                            false
+                        }
+                    })
+                    .expect("Heights cover every way of stacking the pieces");
+                cells.extend((0..height).map(|row| to_position::<H>(col as Col, row as Row)));
+                left -= height;
+            }
+
+            let mask = cells.iter().fold(0, |acc, position| acc | 1 << position);
+            let red = index::sample(rng, ply, ply.div_ceil(2))
+                .into_iter()
+                .fold(0, |acc, ind| acc | 1 << cells[ind]);
+
+            let board = Self::from_pieces(&[red, mask ^ red], win_length);
+            if board.validate().is_ok() {
+                return board;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::StandardBoard;
+
+    /// Every position reachable in exactly `ply` moves, games stop once won
+    fn reachable_positions(ply: usize) -> HashSet<u64> {
+        let mut positions = HashSet::from([StandardBoard::default()]);
+        for depth in 0..ply {
+            let player = if depth % 2 == 0 {
+                Player::Red
+            } else {
+                Player::Yellow
+            };
+            positions = positions
+                .into_iter()
+                .filter(|board| !board.has_won(player.opponent()))
+                .flat_map(|board| {
+                    (0..7).filter_map(move |col| {
+                        let mut board = board;
+                        board.play(player, col).ok().map(|_| board)
+                    })
+                })
+                .collect();
+        }
+        positions.into_iter().map(|board| board.key()).collect()
+    }
+
+    #[test]
+    fn played_games_are_valid() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let mut board = StandardBoard::default();
+            let mut player = Player::Red;
+            loop {
+                assert_eq!(board.validate(), Ok(()), "\n{board}");
+                let moves = board.valid_moves();
+                let Some(col) = (0..7).filter(|col| moves[*col as usize]).choose(&mut rng) else {
+                    break;
+                };
+                let row = board.play(player, col).unwrap();
+                if board.check_win(row, col, player) {
+                    assert_eq!(board.validate(), Ok(()), "\n{board}");
+                    break;
+                }
+                player = player.opponent();
+            }
+        }
+    }
+
+    #[test]
+    fn extra_player() {
+        let board: Board<9, 6> = "|r|y|g| | | | | | |".parse().unwrap();
+        assert_eq!(
+            board.validate(),
+            Err(Unreachable::ExtraPlayer {
+                player: Player::Green
+            })
+        );
+    }
+
+    #[test]
+    fn piece_count_imbalance() {
+        let board: StandardBoard = "|y| | | | | | |".parse().unwrap();
+        assert_eq!(
+            board.validate(),
+            Err(Unreachable::PieceCountImbalance { red: 0, yellow: 1 })
+        );
+        let board: StandardBoard = "|r|r| | | | | |".parse().unwrap();
+        assert_eq!(
+            board.validate(),
+            Err(Unreachable::PieceCountImbalance { red: 2, yellow: 0 })
+        );
+    }
+
+    #[test]
+    fn multiple_winners() {
+        let board: StandardBoard = "
+            |r|y| | | | | |
+            |r|y| | | | | |
+            |r|y| | | | | |
+            |r|y| | | | | |
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(board.validate(), Err(Unreachable::MultipleWinners));
+    }
+
+    #[test]
+    fn play_after_win() {
+        // Yellow played after red completed the column
+        let board: StandardBoard = "
+            |r| | | | | | |
+            |r| | | | | | |
+            |r|y| | | | | |
+            |r|y|y| |y| | |
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            board.validate(),
+            Err(Unreachable::PlayAfterWin {
+                winner: Player::Red
+            })
+        );
+
+        // Two separate lines can not be completed by a single move
+        let board: StandardBoard = "
+            |r| | | | | |r|
+            |r| | | | | |r|
+            |r|y|y| | |y|r|
+            |r|y|y| |y|y|r|
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            board.validate(),
+            Err(Unreachable::PlayAfterWin {
+                winner: Player::Red
+            })
+        );
+    }
+
+    #[test]
+    fn no_move_order() {
+        // Red has to move first but its only piece is above yellow's
+        let board: StandardBoard = "
+            |r| | | | | | |
+            |y| | | | | | |
+            "
+        .parse()
+        .unwrap();
+        assert_eq!(board.validate(), Err(Unreachable::NoMoveOrder));
+    }
+
+    #[test]
+    fn validate_matches_reachable_positions() {
+        for ply in 0..=4 {
+            let reachable = reachable_positions(ply);
+            let mut rng = StdRng::seed_from_u64(ply as u64);
+            let drawn = (0..reachable.len() * 40)
+                .map(|_| StandardBoard::random_reachable(&mut rng, ply))
+                .inspect(|board| assert_eq!(board.mask.count_ones() as usize, ply))
+                .map(|board| board.key())
+                .collect::<HashSet<_>>();
+            assert_eq!(drawn, reachable, "ply {ply}");
+        }
+        // Published counts of positions after each ply
+        let counts = [1, 7, 49, 238, 1120];
+        assert!((0..=4).all(|ply| reachable_positions(ply).len() == counts[ply]));
+    }
+
+    #[test]
+    fn random_reachable_small_board() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for ply in 0..=6 {
+            let board = Board::<3, 3>::random_reachable_with_win_length(&mut rng, ply, 3);
+            assert_eq!(board.mask.count_ones() as usize, ply);
+            assert_eq!(board.win_length(), 3);
+            assert_eq!(board.validate(), Ok(()), "\n{board}");
+        }
+    }
+
+    #[test]
+    fn random_reachable_late_game() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for ply in [20, 30, 40, 42] {
+            let board = StandardBoard::random_reachable(&mut rng, ply);
+            assert_eq!(board.mask.count_ones() as usize, ply);
+            assert_eq!(board.validate(), Ok(()));
+        }
+    }
+}
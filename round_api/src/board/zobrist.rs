@@ -0,0 +1,51 @@
+//! Zobrist keys used to hash positions.
+//! They are generated at compile time from a fixed seed,
+//! so hashes are stable between runs and can be stored
+
+use crate::{BOARD_SIZE, MAX_PLAYERS};
+
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// One step of the splitmix64 generator, returns the next state and its output
+const fn split_mix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys<const N: usize>() -> [[u64; BOARD_SIZE]; N] {
+    let mut keys = [[0; BOARD_SIZE]; N];
+    let mut state = SEED;
+    let mut player = 0;
+    while player < N {
+        let mut cell = 0;
+        while cell < BOARD_SIZE {
+            let (next, key) = split_mix(state);
+            keys[player][cell] = key;
+            state = next;
+            cell += 1;
+        }
+        player += 1;
+    }
+    keys
+}
+
+/// Key of every cell for each player, indexed by player then bit position.
+/// Keys of later players come after the ones of earlier players,
+/// so two-player positions keep their keys whatever the number of players
+pub(super) static KEYS: [[u64; BOARD_SIZE]; MAX_PLAYERS] = generate_keys();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn keys_are_distinct() {
+        let keys: HashSet<_> = KEYS.iter().flatten().copied().collect();
+        assert_eq!(keys.len(), MAX_PLAYERS * BOARD_SIZE);
+        assert!(!keys.contains(&0));
+    }
+}
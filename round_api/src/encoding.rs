@@ -0,0 +1,204 @@
+//! Encoding of positions as fixed length `f32` vectors,
+//! shared by NEAT genomes and any other network consuming positions.
+//!
+//! Planes hold one value per cell, bottom row first and left to right,
+//! so the layout does not depend on how the board stores its pieces
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{to_position, Board, Col, Row},
+    piece::Piece,
+    player::Player,
+};
+
+/// Block of values stacked into the network input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Plane {
+    /// Pieces of the player to move
+    Own,
+    /// Pieces of the other player
+    Opponent,
+    /// Cells without any piece
+    Empty,
+    /// Filled with ones when red is to move, zeros otherwise
+    SideToMove,
+    /// One value per column, set when the column can be played
+    LegalMoves,
+}
+
+impl Plane {
+    /// Number of values of the plane on a `W`x`H` board
+    pub fn size<const W: usize, const H: usize>(self) -> usize {
+        match self {
+            Plane::LegalMoves => W,
+            _ => W * H,
+        }
+    }
+}
+
+/// Turns positions into network inputs made of the configured planes, in order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Encoder {
+    planes: Vec<Plane>,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new([Plane::Own, Plane::Opponent, Plane::Empty, Plane::SideToMove])
+    }
+}
+
+impl Encoder {
+    pub fn new(planes: impl IntoIterator<Item = Plane>) -> Self {
+        Self {
+            planes: planes.into_iter().collect(),
+        }
+    }
+
+    pub fn planes(&self) -> &[Plane] {
+        &self.planes
+    }
+
+    /// Length of the encoded vector on a `W`x`H` board
+    pub fn size<const W: usize, const H: usize>(&self) -> usize {
+        self.planes.iter().map(|plane| plane.size::<W, H>()).sum()
+    }
+
+    pub fn encode<const W: usize, const H: usize>(
+        &self,
+        board: &Board<W, H>,
+        to_move: Player,
+    ) -> Vec<f32> {
+        let mut out = vec![0.; self.size::<W, H>()];
+        self.encode_into(board, to_move, false, &mut out);
+        out
+    }
+
+    /// Encoding of the board flipped left to right, used to augment training data.
+    /// Policy targets have to be flipped the same way with [`mirror_columns`]
+    pub fn encode_mirrored<const W: usize, const H: usize>(
+        &self,
+        board: &Board<W, H>,
+        to_move: Player,
+    ) -> Vec<f32> {
+        let mut out = vec![0.; self.size::<W, H>()];
+        self.encode_into(board, to_move, true, &mut out);
+        out
+    }
+
+    /// Writes the encoding into `out` without allocating
+    ///
+    /// Panics if `out` does not have the length given by [`Encoder::size`]
+    pub fn encode_into<const W: usize, const H: usize>(
+        &self,
+        board: &Board<W, H>,
+        to_move: Player,
+        mirror: bool,
+        out: &mut [f32],
+    ) {
+        assert_eq!(out.len(), self.size::<W, H>(), "Wrong output length");
+        let cells = board.get_array();
+        let legal = board.valid_moves();
+        let source_col = |col: usize| if mirror { W - 1 - col } else { col };
+
+        let mut offset = 0;
+        for plane in &self.planes {
+            let values = &mut out[offset..offset + plane.size::<W, H>()];
+            match plane {
+                Plane::LegalMoves => {
+                    for (col, value) in values.iter_mut().enumerate() {
+                        *value = f32::from(u8::from(legal[source_col(col)]));
+                    }
+                }
+                Plane::SideToMove => {
+                    values.fill(f32::from(u8::from(to_move == Player::Red)));
+                }
+                Plane::Own | Plane::Opponent | Plane::Empty => {
+                    for (ind, value) in values.iter_mut().enumerate() {
+                        let (row, col) = (ind / W, source_col(ind % W));
+                        let Piece(piece) = cells[to_position::<H>(col as Col, row as Row)];
+                        let set = match plane {
+                            Plane::Own => piece == Some(to_move),
+                            Plane::Opponent => piece.is_some_and(|player| player != to_move),
+                            _ => piece.is_none(),
+                        };
+                        *value = f32::from(u8::from(set));
+                    }
+                }
+            }
+            offset += plane.size::<W, H>();
+        }
+    }
+}
+
+/// Flips per column values, such as policy targets, left to right
+pub fn mirror_columns(values: &[f32]) -> Vec<f32> {
+    values.iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::StandardBoard;
+
+    #[test]
+    fn lengths() {
+        assert_eq!(Encoder::default().size::<7, 6>(), 4 * 42);
+        let encoder = Encoder::new([Plane::Own, Plane::LegalMoves]);
+        assert_eq!(encoder.size::<7, 6>(), 42 + 7);
+        assert_eq!(
+            encoder.encode(&StandardBoard::default(), Player::Red).len(),
+            49
+        );
+    }
+
+    #[test]
+    fn planes_are_relative_to_the_player_to_move() {
+        let board = StandardBoard::from_moves("11").unwrap();
+        let encoder = Encoder::new([Plane::Own, Plane::Opponent, Plane::Empty]);
+        let red = encoder.encode(&board, Player::Red);
+        // Red at the bottom left, yellow right above it
+        assert_eq!(red[0], 1.);
+        assert_eq!(red[42 + 7], 1.);
+        assert_eq!(red.iter().sum::<f32>(), 42.);
+        assert_eq!(red[84..].iter().sum::<f32>(), 40.);
+
+        let yellow = encoder.encode(&board, Player::Yellow);
+        assert_eq!(yellow[..42], red[42..84]);
+        assert_eq!(yellow[42..84], red[..42]);
+    }
+
+    #[test]
+    fn side_to_move_and_legal_moves() {
+        let board = StandardBoard::from_moves("111111").unwrap();
+        let encoder = Encoder::new([Plane::SideToMove, Plane::LegalMoves]);
+        let red = encoder.encode(&board, Player::Red);
+        assert!(red[..42].iter().all(|value| *value == 1.));
+        assert_eq!(red[42..], [0., 1., 1., 1., 1., 1., 1.]);
+        let yellow = encoder.encode(&board, Player::Yellow);
+        assert!(yellow[..42].iter().all(|value| *value == 0.));
+    }
+
+    #[test]
+    fn mirrored_encoding() {
+        let encoder = Encoder::new([Plane::Own, Plane::Opponent, Plane::LegalMoves]);
+        let board = StandardBoard::from_moves("1121113").unwrap();
+        let mirrored = StandardBoard::from_moves("7767775").unwrap();
+        assert_eq!(
+            encoder.encode_mirrored(&board, Player::Yellow),
+            encoder.encode(&mirrored, Player::Yellow)
+        );
+        assert_eq!(
+            mirror_columns(&[0.5, 0.25, 0., 0., 0., 0., 0.25]),
+            [0.25, 0., 0., 0., 0., 0.25, 0.5]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_output_length() {
+        let mut out = [0.; 10];
+        Encoder::default().encode_into(&StandardBoard::default(), Player::Red, false, &mut out);
+    }
+}
@@ -0,0 +1,271 @@
+//! Static evaluation of positions, used by depth-limited agents
+//! to score the positions where they stop searching.
+//!
+//! Scores are given for one player in a two-player game, positive when the position
+//! favours that player, and the score for the other player is always the opposite
+
+use std::{fmt::Display, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{get_col, Board, Col},
+    player::Player,
+};
+
+/// Heuristic score of positions that are not over yet
+pub trait Evaluator {
+    /// Score of `board` for `player`, the higher the better for `player`
+    fn evaluate<const W: usize, const H: usize>(&self, board: &Board<W, H>, player: Player) -> f32;
+}
+
+/// Reasons weights could not be loaded from a file
+#[derive(Debug)]
+pub enum LoadWeightsError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+}
+
+impl Display for LoadWeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadWeightsError::Io(err) => write!(f, "Could not read the weights: {err}"),
+            LoadWeightsError::Parse(err) => write!(f, "Could not parse the weights: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadWeightsError {}
+
+impl From<io::Error> for LoadWeightsError {
+    fn from(err: io::Error) -> Self {
+        LoadWeightsError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for LoadWeightsError {
+    fn from(err: serde_json::Error) -> Self {
+        LoadWeightsError::Parse(err)
+    }
+}
+
+/// Weight of every feature of [`Heuristic`].
+/// Each feature is counted for both players and the difference is weighted.
+/// Twos and threes are named after classic Connect 4, where lines are four long:
+/// they are lines missing two pieces and one piece, the other cells being empty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Weights {
+    /// Lines holding all but two pieces of a player and nothing else
+    pub open_twos: f32,
+    /// Lines holding all but one piece of a player and nothing else
+    pub open_threes: f32,
+    /// Pieces in the middle column, or the two middle ones on boards of even width
+    pub center: f32,
+    /// Columns holding a threat on a row that suits the player,
+    /// odd rows for red and even rows for yellow
+    pub threat_parity: f32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            open_twos: 1.,
+            open_threes: 4.,
+            center: 2.,
+            threat_parity: 8.,
+        }
+    }
+}
+
+impl Weights {
+    /// Reads weights stored as JSON, missing features keep their default weight
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Weights are plain numbers")
+    }
+
+    /// Reads weights from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadWeightsError> {
+        Ok(Self::from_json(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+/// Counts of the features of a position for one player
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Features {
+    open_twos: u32,
+    open_threes: u32,
+    center: u32,
+    threat_parity: u32,
+}
+
+impl Features {
+    fn weighted(self, weights: &Weights) -> f32 {
+        weights.open_twos * self.open_twos as f32
+            + weights.open_threes * self.open_threes as f32
+            + weights.center * self.center as f32
+            + weights.threat_parity * self.threat_parity as f32
+    }
+}
+
+/// Evaluator built from a weighted sum of hand written features, read from the bitboards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Heuristic {
+    weights: Weights,
+}
+
+impl Heuristic {
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    pub fn weights(&self) -> &Weights {
+        &self.weights
+    }
+
+    fn features<const W: usize, const H: usize>(board: &Board<W, H>, player: Player) -> Features {
+        let own = board.pieces(player);
+        let others = board.pieces(player.opponent());
+        let mut features = Features::default();
+        for window in board.windows() {
+            if window & others != 0 {
+                continue;
+            }
+            let missing = (window & !own).count_ones() as usize;
+            match board.win_length() - missing {
+                // Empty lines are never counted, even when lines are only two long
+                pieces if pieces + 2 == board.win_length() && pieces > 0 => features.open_twos += 1,
+                pieces if pieces + 1 == board.win_length() && pieces > 0 => {
+                    features.open_threes += 1
+                }
+                _ => {}
+            }
+        }
+
+        let center =
+            (W.saturating_sub(1) / 2..=W / 2).fold(0, |acc, col| acc | get_col::<W, H>(col as Col));
+        features.center = (own & center).count_ones();
+
+        let threats = match player {
+            Player::Red => board.odd_threats(player),
+            _ => board.even_threats(player),
+        };
+        features.threat_parity = threats.iter().filter(|threat| **threat).count() as u32;
+        features
+    }
+}
+
+impl Evaluator for Heuristic {
+    fn evaluate<const W: usize, const H: usize>(&self, board: &Board<W, H>, player: Player) -> f32 {
+        Self::features(board, player).weighted(&self.weights)
+            - Self::features(board, player.opponent()).weighted(&self.weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use crate::board::StandardBoard;
+
+    use super::*;
+
+    #[test]
+    fn scores_are_opposite() {
+        let heuristic = Heuristic::default();
+        assert_eq!(
+            heuristic.evaluate(&StandardBoard::default(), Player::Red),
+            0.
+        );
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let board = StandardBoard::from_rng(&mut rng);
+            assert_eq!(
+                heuristic.evaluate(&board, Player::Red),
+                -heuristic.evaluate(&board, Player::Yellow),
+                "{board}"
+            );
+        }
+    }
+
+    #[test]
+    fn features_are_counted() {
+        let board: StandardBoard = "
+            | | | | | | | |
+            | | | |y| | | |
+            | | |r|r|r| | |
+            "
+        .parse()
+        .unwrap();
+        let red = Heuristic::features(&board, Player::Red);
+        // Bottom row lines starting in the second and third columns
+        assert_eq!(red.open_threes, 2);
+        assert_eq!(red.center, 1);
+        // Both ends of the row complete a line on the first row
+        assert_eq!(red.threat_parity, 2);
+        let yellow = Heuristic::features(&board, Player::Yellow);
+        assert_eq!(yellow.open_threes, 0);
+        assert_eq!(yellow.threat_parity, 0);
+        assert_eq!(yellow.center, 1);
+    }
+
+    #[test]
+    fn full_height_columns() {
+        // The only column takes the whole bitboard
+        let mut board = Board::<1, 64>::with_win_length(4);
+        assert_eq!(Heuristic::default().evaluate(&board, Player::Red), 0.);
+        board.play(Player::Red, 0).unwrap();
+        let score = Heuristic::default().evaluate(&board, Player::Red);
+        assert_eq!(score, Weights::default().center);
+    }
+
+    #[test]
+    fn center_is_preferred() {
+        let heuristic = Heuristic::new(Weights {
+            open_twos: 0.,
+            open_threes: 0.,
+            center: 1.,
+            threat_parity: 0.,
+        });
+        let center = StandardBoard::from_moves("4").unwrap();
+        let edge = StandardBoard::from_moves("1").unwrap();
+        assert_eq!(heuristic.evaluate(&center, Player::Red), 1.);
+        assert_eq!(heuristic.evaluate(&edge, Player::Red), 0.);
+        assert!(
+            Heuristic::default().evaluate(&center, Player::Red)
+                > Heuristic::default().evaluate(&edge, Player::Red)
+        );
+    }
+
+    #[test]
+    fn weights_from_json() {
+        let weights = Weights {
+            open_twos: 0.5,
+            ..Weights::default()
+        };
+        assert_eq!(Weights::from_json(&weights.to_json()).unwrap(), weights);
+        // Missing features keep their default weight
+        assert_eq!(
+            Weights::from_json(r#"{"open_twos": 0.5}"#).unwrap(),
+            weights
+        );
+        assert!(Weights::from_json(r#"{"open_twos": "high"}"#).is_err());
+        assert!(matches!(
+            Weights::load("no/such/weights.json"),
+            Err(LoadWeightsError::Io(_))
+        ));
+
+        let path = std::env::temp_dir().join("round_api_weights.json");
+        weights.save(&path).unwrap();
+        assert_eq!(Weights::load(&path).unwrap(), weights);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,15 +1,23 @@
-use crate::board::*;
+use crate::{rules::Ruleset, state::GameState};
 
-pub(crate) struct RoundAPI<T: GameTrait> {
+/// Round of a game played under the ruleset `R`, at the stage `T`
+pub struct RoundAPI<T: GameTrait, R = GameState> {
     pub(crate) state: T,
-    pub(crate) board: Board,
+    pub(crate) game: R,
 }
 
-impl<T: GameTrait> RoundAPI<T> {
-    pub fn get_board(&self) -> &Board {
-        &self.board
+impl<T: GameTrait, R: Ruleset> RoundAPI<T, R> {
+    pub fn get_board(&self) -> &R::Board {
+        self.game.board()
+    }
+
+    pub fn get_game(&self) -> &R {
+        &self.game
+    }
+
+    pub fn get_state(&self) -> &T {
+        &self.state
     }
 }
 
 pub trait GameTrait {}
-
@@ -2,3 +2,7 @@ mod game;
 mod play;
 mod player_interaction;
 mod start;
+
+pub use game::{GameTrait, RoundAPI};
+pub use play::Play;
+pub use start::{RoundStart, Start};
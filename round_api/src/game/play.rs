@@ -1,5 +1,165 @@
-use super::game::GameTrait;
+use crate::{
+    board::{GamePlay, IllegalMove, TerminatedStatus},
+    player_agent::agent::PlayerTrait,
+    rules::Ruleset,
+};
+
+use super::game::{GameTrait, RoundAPI};
 
 #[derive(Default)]
 pub struct Play {}
 impl GameTrait for Play {}
+
+impl<R: Ruleset + 'static> RoundAPI<Play, R> {
+    /// Asks the agent of the player to move for a move and makes it
+    pub fn next_turn(
+        &mut self,
+        red: &mut dyn PlayerTrait<R>,
+        yellow: &mut dyn PlayerTrait<R>,
+    ) -> GamePlay {
+        self.next_turn_among(&mut [red, yellow])
+    }
+
+    /// Same as [`RoundAPI::next_turn`] for games of any number of players,
+    /// `agents` holding one agent per player in the order of [`Player::ALL`](crate::player::Player::ALL)
+    ///
+    /// Panics if the player to move has no agent
+    pub fn next_turn_among(&mut self, agents: &mut [&mut dyn PlayerTrait<R>]) -> GamePlay {
+        if self.game.is_terminated() {
+            return GamePlay::InvalidBoard(IllegalMove::GameOver);
+        }
+        let player = self.game.to_move();
+        let agent = agents
+            .get_mut(player as usize)
+            .unwrap_or_else(|| panic!("No agent playing for {player:?}"));
+        let mv = agent.play(&self.game);
+        self.game.apply_move(player, mv)
+    }
+
+    /// Plays turns until the end of the game.
+    /// An agent making an illegal move forfeits the game
+    pub fn play_out(
+        &mut self,
+        red: &mut dyn PlayerTrait<R>,
+        yellow: &mut dyn PlayerTrait<R>,
+    ) -> TerminatedStatus {
+        self.play_out_among(&mut [red, yellow])
+    }
+
+    /// Same as [`RoundAPI::play_out`] for games of any number of players.
+    /// An agent making an illegal move is out of the game, the others play on
+    pub fn play_out_among(&mut self, agents: &mut [&mut dyn PlayerTrait<R>]) -> TerminatedStatus {
+        if let Some(status) = self.game.result() {
+            return status;
+        }
+        loop {
+            let player = self.game.to_move();
+            match self.next_turn_among(agents) {
+                GamePlay::ValidPlay => {}
+                GamePlay::GameTerminated(status) => return status,
+                GamePlay::InvalidBoard(_) => {
+                    if let Some(status) = self.game.forfeit(player) {
+                        return status;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game::RoundStart,
+        player::Player,
+        player_agent::agent::MockPlayerTrait,
+        rules::{Move, PopOut},
+        state::{GameState, StandardGame},
+    };
+
+    use super::*;
+
+    fn agent<R: 'static>(moves: Vec<Move>) -> MockPlayerTrait<R> {
+        let mut agent = MockPlayerTrait::new();
+        let mut moves = moves.into_iter().cycle();
+        agent
+            .expect_play()
+            .returning(move |_| moves.next().unwrap());
+        agent
+    }
+
+    #[test]
+    fn classic_game() {
+        let mut round = RoundStart::<StandardGame>::new().start_game();
+        let mut red = agent(vec![Move::Drop(0)]);
+        let mut yellow = agent(vec![Move::Drop(1)]);
+        assert_eq!(round.next_turn(&mut red, &mut yellow), GamePlay::ValidPlay);
+        assert_eq!(round.get_game().to_move(), Player::Yellow);
+        assert_eq!(
+            round.play_out(&mut red, &mut yellow),
+            TerminatedStatus::Win(Player::Red)
+        );
+        assert_eq!(round.get_board().history(), &[0, 1, 0, 1, 0, 1, 0]);
+        assert_eq!(
+            round.next_turn(&mut red, &mut yellow),
+            GamePlay::InvalidBoard(IllegalMove::GameOver)
+        );
+    }
+
+    #[test]
+    fn illegal_moves_forfeit() {
+        let mut round = RoundStart::<StandardGame>::new().start_game();
+        let mut red = agent(vec![Move::Drop(3), Move::Pop(3)]);
+        let mut yellow = agent(vec![Move::Drop(3)]);
+        assert_eq!(
+            round.play_out(&mut red, &mut yellow),
+            TerminatedStatus::Win(Player::Yellow)
+        );
+        assert_eq!(
+            round.get_game().result(),
+            Some(TerminatedStatus::Win(Player::Yellow))
+        );
+    }
+
+    #[test]
+    fn three_player_game() {
+        let mut round = RoundStart::with_game(GameState::<9, 6>::with_players(3, 4)).start_game();
+        let mut red = agent(vec![Move::Drop(0)]);
+        let mut yellow = agent(vec![Move::Drop(1)]);
+        let mut green = agent(vec![Move::Drop(2)]);
+        let mut agents: [&mut dyn PlayerTrait<_>; 3] = [&mut red, &mut yellow, &mut green];
+        while round.next_turn_among(&mut agents) == GamePlay::ValidPlay {}
+        assert_eq!(
+            round.get_game().result(),
+            Some(TerminatedStatus::Win(Player::Red))
+        );
+        assert_eq!(round.get_board().history().len(), 10);
+    }
+
+    #[test]
+    fn players_forfeiting_are_out() {
+        let mut round = RoundStart::with_game(GameState::<9, 6>::with_players(3, 4)).start_game();
+        let mut red = agent(vec![Move::Drop(0)]);
+        let mut yellow = agent(vec![Move::Drop(1)]);
+        let mut green = agent(vec![Move::Pop(2)]);
+        let mut agents: [&mut dyn PlayerTrait<_>; 3] = [&mut red, &mut yellow, &mut green];
+        assert_eq!(
+            round.play_out_among(&mut agents),
+            TerminatedStatus::Win(Player::Red)
+        );
+        assert!(!round.get_game().in_game(Player::Green));
+        assert_eq!(round.get_board().history(), &[0, 1, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn popout_game() {
+        let mut round = RoundStart::<PopOut<7, 6>>::new().start_game();
+        let mut red = agent(vec![Move::Drop(0), Move::Pop(0)]);
+        let mut yellow = agent(vec![Move::Drop(6), Move::Pop(6)]);
+        assert_eq!(
+            round.play_out(&mut red, &mut yellow),
+            TerminatedStatus::Draw
+        );
+        assert_eq!(round.get_game().ply(), 8);
+    }
+}
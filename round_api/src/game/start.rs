@@ -1,4 +1,4 @@
-use crate::board::Board;
+use crate::state::GameState;
 
 use super::{
     game::{GameTrait, RoundAPI},
@@ -10,25 +10,29 @@ pub struct Start;
 
 impl GameTrait for Start {}
 
-impl Default for RoundAPI<Start> {
+impl<R: Default> Default for RoundAPI<Start, R> {
     fn default() -> Self {
-        Self {
-            board: Board::default(),
-            state: Start::default(),
-        }
+        Self::with_game(R::default())
     }
 }
 
-pub type RoundStart = RoundAPI<Start>;
+pub type RoundStart<R = GameState> = RoundAPI<Start, R>;
 
-impl RoundStart {
+impl<R: Default> RoundStart<R> {
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<R> RoundStart<R> {
+    /// Round starting from the given game, of any ruleset
+    pub fn with_game(game: R) -> Self {
+        Self { state: Start, game }
+    }
 
-    pub fn start_game(self) -> RoundAPI<Play> {
+    pub fn start_game(self) -> RoundAPI<Play, R> {
         RoundAPI {
-            board: self.board,
+            game: self.game,
             state: Play::default(),
         }
     }
@@ -40,8 +44,8 @@ mod tests {
 
     #[test]
     fn test_start_state() {
-        let game_start = RoundStart::new();
-        assert_eq!(game_start.state, Start::default());
-        assert_eq!(game_start.board, Board::default());
+        let game_start = RoundStart::<GameState>::new();
+        assert_eq!(game_start.state, Start);
+        assert_eq!(game_start.game, GameState::default());
     }
 }
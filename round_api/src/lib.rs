@@ -1,9 +1,18 @@
 pub const BOARD_SIZE: usize = 64;
 pub const WIDTH: usize = 8;
 pub const HEIGHT: usize = 8;
+pub const WIN_LENGTH: usize = 4;
+/// Most players a game can be played with
+pub const MAX_PLAYERS: usize = 4;
 
 pub mod board;
+pub mod encoding;
+pub mod evaluation;
 pub mod game;
+pub mod network;
 pub mod piece;
 pub mod player;
 pub mod player_agent;
+pub mod rules;
+pub mod solver;
+pub mod state;
@@ -0,0 +1,109 @@
+//! Networks guiding tree search, giving a prior over the legal moves of a position
+//! and an estimate of its value, as the policy and value heads of AlphaZero.
+//!
+//! Values are given for the player to move in a two-player game,
+//! from -1 for a certain loss to 1 for a certain win
+
+use mockall::*;
+
+use crate::{
+    board::Board,
+    evaluation::Evaluator,
+    rules::{Move, Ruleset},
+    state::GameState,
+};
+
+/// Output of a network for one position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    /// Weight of every legal move, in the order of [`Ruleset::legal_moves`].
+    /// They are normalised by the search, so they do not have to add up to one
+    pub priors: Vec<f32>,
+    /// Expected outcome for the player to move, between -1 and 1
+    pub value: f32,
+}
+
+impl Prediction {
+    /// Same weight for every move
+    pub fn uniform(moves: &[Move], value: f32) -> Self {
+        Self {
+            priors: vec![1. / moves.len().max(1) as f32; moves.len()],
+            value,
+        }
+    }
+}
+
+/// Policy and value of positions, generic over the ruleset as agents are
+#[automock]
+pub trait Network<R: 'static = GameState> {
+    fn predict(&mut self, game: &R) -> Prediction;
+}
+
+/// Network knowing nothing about the game, every move as likely and every position even
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Uniform;
+
+impl<R: Ruleset + 'static> Network<R> for Uniform {
+    fn predict(&mut self, game: &R) -> Prediction {
+        Prediction::uniform(&game.legal_moves(), 0.)
+    }
+}
+
+/// Network backed by a static [`Evaluator`], with uniform priors.
+/// Scores are squashed into values with `tanh(score / scale)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvaluatorNetwork<E> {
+    evaluator: E,
+    scale: f32,
+}
+
+impl<E: Evaluator> EvaluatorNetwork<E> {
+    /// Panics if `scale` is not positive
+    pub fn new(evaluator: E, scale: f32) -> Self {
+        assert!(scale > 0., "The scale of the scores has to be positive");
+        Self { evaluator, scale }
+    }
+
+    pub fn evaluator(&self) -> &E {
+        &self.evaluator
+    }
+}
+
+impl<E, R, const W: usize, const H: usize> Network<R> for EvaluatorNetwork<E>
+where
+    E: Evaluator,
+    R: Ruleset<Board = Board<W, H>> + 'static,
+{
+    fn predict(&mut self, game: &R) -> Prediction {
+        let score = self.evaluator.evaluate(game.board(), game.to_move());
+        Prediction::uniform(&game.legal_moves(), (score / self.scale).tanh())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluation::Heuristic, state::StandardGame};
+
+    use super::*;
+
+    #[test]
+    fn uniform_priors() {
+        let mut game = StandardGame::default();
+        for _ in 0..6 {
+            game.apply(game.to_move(), 0);
+        }
+        let prediction = Uniform.predict(&game);
+        assert_eq!(prediction.priors, vec![1. / 6.; 6]);
+        assert_eq!(prediction.value, 0.);
+    }
+
+    #[test]
+    fn values_follow_the_evaluator() {
+        let mut network = EvaluatorNetwork::new(Heuristic::default(), 10.);
+        assert_eq!(network.predict(&StandardGame::default()).value, 0.);
+        // Red has an open three on the bottom row
+        let game = StandardGame::from_moves("44556").unwrap();
+        let value = network.predict(&game).value;
+        assert!(value < 0. && value > -1., "{value}");
+    }
+}
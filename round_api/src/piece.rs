@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 use crate::player::Player;
@@ -7,13 +8,13 @@ macro_rules! piece {
     () => {
         Piece(None)
     };
-    
+
     ($r:ident) => {
         Piece(Some($crate::player!($r)))
     };
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Piece(pub Option<Player>);
 
 impl Display for Piece {
@@ -1,11 +1,59 @@
 use colored::Colorize;
 use rand_derive2::RandGen;
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, RandGen)]
+use crate::MAX_PLAYERS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, RandGen, Serialize, Deserialize)]
 pub enum Player {
     Red,
     Yellow,
+    Green,
+    Blue,
+}
+
+impl Player {
+    /// Every player in turn order, a game with `n` players uses the first `n`
+    pub const ALL: [Player; MAX_PLAYERS] =
+        [Player::Red, Player::Yellow, Player::Green, Player::Blue];
+
+    /// The player moving after this one in a two-player game
+    pub fn opponent(self) -> Player {
+        self.next(2)
+    }
+
+    /// The player moving after this one in a game of `players` players
+    ///
+    /// Panics if this player does not take part in such a game
+    pub fn next(self, players: usize) -> Player {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&players) && (self as usize) < players,
+            "{self:?} does not play in a game of {players} players"
+        );
+        Self::ALL[(self as usize + 1) % players]
+    }
+
+    /// The player who moved before this one in a game of `players` players
+    ///
+    /// Panics if this player does not take part in such a game
+    pub fn previous(self, players: usize) -> Player {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&players) && (self as usize) < players,
+            "{self:?} does not play in a game of {players} players"
+        );
+        Self::ALL[(self as usize + players - 1) % players]
+    }
+
+    /// Letter standing for the player, as read by [`FromStr`]
+    pub fn letter(self) -> char {
+        match self {
+            Player::Red => 'r',
+            Player::Yellow => 'y',
+            Player::Green => 'g',
+            Player::Blue => 'b',
+        }
+    }
 }
 
 impl FromStr for Player {
@@ -14,6 +62,8 @@ impl FromStr for Player {
         match s {
             "r" => Ok(Player::Red),
             "y" => Ok(Player::Yellow),
+            "g" => Ok(Player::Green),
+            "b" => Ok(Player::Blue),
             _ => Err(()),
         }
     }
@@ -27,6 +77,8 @@ impl Display for Player {
             match self {
                 Player::Red => "*".red(),
                 Player::Yellow => "*".cyan(),
+                Player::Green => "*".green(),
+                Player::Blue => "*".blue(),
             }
         )
     }
@@ -44,9 +96,44 @@ macro_rules! player {
 mod tests {
     use super::*;
 
+    #[test]
+    fn letters_round_trip() {
+        for player in Player::ALL {
+            assert_eq!(player.letter().to_string().parse(), Ok(player));
+        }
+    }
+
     #[test]
     fn test_macros() {
         assert_eq!(player!(r), Player::Red);
         assert_eq!(player!(y), Player::Yellow);
+        assert_eq!(player!(g), Player::Green);
+        assert_eq!(player!(b), Player::Blue);
+    }
+
+    #[test]
+    fn test_opponent() {
+        assert_eq!(Player::Red.opponent(), Player::Yellow);
+        assert_eq!(Player::Yellow.opponent(), Player::Red);
+    }
+
+    #[test]
+    fn turn_order() {
+        assert_eq!(Player::Yellow.next(3), Player::Green);
+        assert_eq!(Player::Green.next(3), Player::Red);
+        assert_eq!(Player::Blue.next(4), Player::Red);
+        assert_eq!(Player::Red.previous(3), Player::Green);
+        assert_eq!(Player::Green.previous(4), Player::Yellow);
+        for players in 2..=MAX_PLAYERS {
+            for player in &Player::ALL[..players] {
+                assert_eq!(player.next(players).previous(players), *player);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_in_the_game() {
+        Player::Blue.next(3);
     }
 }
@@ -1,8 +1,10 @@
 use mockall::*;
 
-use crate::board::{Board, Col};
+use crate::{rules::Move, state::GameState};
 
+/// Agent choosing the next move of a game.
+/// Generic over the ruleset, the game state of any variant and board size
 #[automock]
-pub trait PlayerTrait {
-    fn play(&mut self, board: &Board) -> Col;
+pub trait PlayerTrait<R: 'static = GameState> {
+    fn play(&mut self, game: &R) -> Move;
 }
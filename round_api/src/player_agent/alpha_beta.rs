@@ -0,0 +1,306 @@
+use rand::prelude::*;
+
+use crate::{
+    board::{Board, TerminatedStatus},
+    evaluation::{Evaluator, Heuristic},
+    rules::{Move, Ruleset},
+};
+
+use super::agent::PlayerTrait;
+
+/// Score of a won game, minus the number of plies it took.
+/// Well above anything an evaluator is expected to return
+const WIN: f32 = 1e6;
+
+/// Agent searching the moves of the next `depth` plies with alpha-beta pruning,
+/// scoring the positions where the search stops with an [`Evaluator`].
+/// Moves are searched center first and games are assumed to have two players.
+///
+/// Equally good moves go to the most central one, or to a random one
+/// when the agent is given a generator
+pub struct AlphaBetaAgent<E = Heuristic> {
+    depth: usize,
+    evaluator: E,
+    rng: Option<Box<dyn RngCore>>,
+    nodes: u64,
+}
+
+impl<E: Evaluator> AlphaBetaAgent<E> {
+    /// Agent looking `depth` plies ahead
+    ///
+    /// Panics if `depth` is zero
+    pub fn new(depth: usize, evaluator: E) -> Self {
+        assert!(depth > 0, "The agent has to look at least one ply ahead");
+        Self {
+            depth,
+            evaluator,
+            rng: None,
+            nodes: 0,
+        }
+    }
+
+    /// Breaks ties between equally good moves at random
+    pub fn with_random_ties(self, rng: Box<dyn RngCore>) -> Self {
+        Self {
+            rng: Some(rng),
+            ..self
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn evaluator(&self) -> &E {
+        &self.evaluator
+    }
+
+    /// Positions visited since the agent was created
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Score of every legal move for the player to move, in the order of the search
+    pub fn score_moves<R, const W: usize, const H: usize>(&mut self, game: &R) -> Vec<(Move, f32)>
+    where
+        R: Ruleset<Board = Board<W, H>>,
+    {
+        let player = game.to_move();
+        Self::ordered::<W>(game.legal_moves())
+            .into_iter()
+            .map(|mv| {
+                let mut child = game.clone();
+                child.apply_move(player, mv);
+                let score = self.negamax(&child, self.depth - 1, -f32::INFINITY, f32::INFINITY, 1);
+                let score = if child.to_move() == player {
+                    score
+                } else {
+                    -score
+                };
+                (mv, score)
+            })
+            .collect()
+    }
+
+    /// Moves sorted from the middle column outwards, drops before pops
+    fn ordered<const W: usize>(mut moves: Vec<Move>) -> Vec<Move> {
+        moves.sort_by_key(|mv| {
+            let distance = (2 * mv.col() as isize - (W as isize - 1)).abs();
+            (matches!(mv, Move::Pop(_)), distance, mv.col())
+        });
+        moves
+    }
+
+    /// Value of the game for the player to move, within the `alpha`..`beta` window
+    fn negamax<R, const W: usize, const H: usize>(
+        &mut self,
+        game: &R,
+        depth: usize,
+        mut alpha: f32,
+        beta: f32,
+        ply: usize,
+    ) -> f32
+    where
+        R: Ruleset<Board = Board<W, H>>,
+    {
+        self.nodes += 1;
+        let player = game.to_move();
+        match game.result() {
+            Some(TerminatedStatus::Draw) => return 0.,
+            Some(TerminatedStatus::Win(winner)) if winner == player => return WIN - ply as f32,
+            Some(TerminatedStatus::Win(_)) => return -(WIN - ply as f32),
+            None => {}
+        }
+        let moves = game.legal_moves();
+        if depth == 0 || moves.is_empty() {
+            return self.evaluator.evaluate(game.board(), player);
+        }
+
+        let mut best = -f32::INFINITY;
+        for mv in Self::ordered::<W>(moves) {
+            let mut child = game.clone();
+            child.apply_move(player, mv);
+            // Some variants give the same player another move
+            let score = if child.to_move() == player {
+                self.negamax(&child, depth - 1, alpha, beta, ply + 1)
+            } else {
+                -self.negamax(&child, depth - 1, -beta, -alpha, ply + 1)
+            };
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// Plays the best move found by the search
+///
+/// Panics if the game has no legal move left
+impl<E, R, const W: usize, const H: usize> PlayerTrait<R> for AlphaBetaAgent<E>
+where
+    E: Evaluator,
+    R: Ruleset<Board = Board<W, H>> + 'static,
+{
+    fn play(&mut self, game: &R) -> Move {
+        let scores = self.score_moves(game);
+        let best = scores
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(-f32::INFINITY, f32::max);
+        let mut best_moves = scores
+            .into_iter()
+            .filter(|(_, score)| *score == best)
+            .map(|(mv, _)| mv);
+        match &mut self.rng {
+            Some(rng) => best_moves.choose(rng),
+            None => best_moves.next(),
+        }
+        .expect("The game has no legal move left")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::{
+        board::{Col, GamePlay},
+        evaluation::Weights,
+        player::Player,
+        rules::PopOut,
+        state::StandardGame,
+    };
+
+    use super::*;
+
+    /// Evaluator seeing every position as even, so only wins and losses matter
+    fn blind() -> Heuristic {
+        Heuristic::new(Weights {
+            open_twos: 0.,
+            open_threes: 0.,
+            center: 0.,
+            threat_parity: 0.,
+        })
+    }
+
+    /// Game reached by playing the columns in turn, skipping full ones, until it is over
+    fn random_game(cols: &[Col]) -> StandardGame {
+        let mut game = StandardGame::default();
+        for col in cols {
+            if game.board().valid_moves()[*col as usize] {
+                let player = game.to_move();
+                if let GamePlay::GameTerminated(_) = game.apply(player, *col) {
+                    game.undo();
+                    break;
+                }
+            }
+        }
+        game
+    }
+
+    #[test]
+    fn takes_immediate_wins() {
+        for depth in 1..=5 {
+            let mut agent = AlphaBetaAgent::new(depth, Heuristic::default());
+            // Vertical win in the first column
+            assert_eq!(
+                agent.play(&StandardGame::from_moves("121212").unwrap()),
+                Move::Drop(0),
+                "depth {depth}"
+            );
+            // Open three on the bottom row
+            let mv = agent.play(&StandardGame::from_moves("445566").unwrap());
+            assert!(matches!(mv, Move::Drop(2) | Move::Drop(6)), "depth {depth}");
+        }
+    }
+
+    #[test]
+    fn blocks_immediate_losses() {
+        for depth in 2..=5 {
+            let mut agent = AlphaBetaAgent::new(depth, Heuristic::default());
+            // Red threatens the first column
+            assert_eq!(
+                agent.play(&StandardGame::from_moves("12121").unwrap()),
+                Move::Drop(0),
+                "depth {depth}"
+            );
+            let mut blind = AlphaBetaAgent::new(depth, blind());
+            assert_eq!(
+                blind.play(&StandardGame::from_moves("12121").unwrap()),
+                Move::Drop(0),
+                "depth {depth}"
+            );
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn wins_and_blocks_are_never_missed(cols in prop::collection::vec(0..7 as Col, 0..40), depth in 1..=4usize) {
+            let game = random_game(&cols);
+            let board = game.board();
+            let player = game.to_move();
+            let wins = board.winning_moves(player);
+            let mv = AlphaBetaAgent::new(depth, Heuristic::default()).play(&game);
+            if wins.contains(&true) {
+                prop_assert!(wins[mv.col() as usize], "{mv:?} played on\n{board}");
+            } else {
+                let blocks = board.forced_blocks(player);
+                let under_threat = board.moves_under_threat(player);
+                // Two threats can not both be blocked, nor a threat right below another one
+                let blockable = blocks.iter().filter(|block| **block).count() == 1
+                    && blocks.iter().zip(under_threat).all(|(block, under)| !(*block && under));
+                if depth >= 2 && blockable {
+                    prop_assert!(blocks[mv.col() as usize], "{mv:?} played on\n{board}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sees_forced_wins() {
+        // Red builds an open three on the bottom row, which can not be stopped
+        let mut agent = AlphaBetaAgent::new(3, blind());
+        let scores = agent.score_moves(&StandardGame::from_moves("4455").unwrap());
+        let (mv, score) = scores
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        assert!(matches!(mv, Move::Drop(2) | Move::Drop(5)));
+        assert_eq!(score, WIN - 3.);
+    }
+
+    #[test]
+    fn ties_are_broken() {
+        let game = StandardGame::default();
+        let mut agent = AlphaBetaAgent::new(2, blind());
+        assert_eq!(agent.play(&game), Move::Drop(3));
+        assert!(agent.nodes() > 0);
+
+        let mut agent =
+            AlphaBetaAgent::new(2, blind()).with_random_ties(Box::new(StdRng::seed_from_u64(9)));
+        let cols = (0..100)
+            .map(|_| agent.play(&game).col())
+            .collect::<std::collections::HashSet<Col>>();
+        assert_eq!(cols.len(), 7);
+    }
+
+    #[test]
+    fn plays_other_rulesets() {
+        let mut game = PopOut::<7, 6>::default();
+        for col in [1, 1, 2, 2, 3, 3] {
+            game.apply_move(game.to_move(), Move::Drop(col));
+        }
+        let mut agent = AlphaBetaAgent::new(3, Heuristic::default());
+        let mv = agent.play(&game);
+        assert!(matches!(mv, Move::Drop(0) | Move::Drop(4)));
+        assert_eq!(
+            game.apply_move(Player::Red, mv),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+    }
+}
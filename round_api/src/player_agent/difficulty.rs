@@ -0,0 +1,187 @@
+use std::fmt::Display;
+
+use rand::{distributions::Bernoulli, prelude::*};
+
+use crate::{
+    board::Board,
+    evaluation::Heuristic,
+    rules::{Move, Ruleset},
+};
+
+use super::{agent::PlayerTrait, alpha_beta::AlphaBetaAgent, rand_agent::RandomAgent};
+
+/// Named levels for bots, from a mostly random player to the full search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Plies searched by the bot
+    pub fn depth(self) -> usize {
+        match self {
+            Difficulty::Easy => 1,
+            Difficulty::Medium => 3,
+            Difficulty::Hard => 5,
+            Difficulty::Expert => 7,
+        }
+    }
+
+    /// Probability of playing a random move instead of the searched one
+    pub fn randomness(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Medium => 0.25,
+            Difficulty::Hard => 0.1,
+            Difficulty::Expert => 0.,
+        }
+    }
+
+    /// Alpha-beta bot of this level, playing the same games for the same seed
+    pub fn agent(self, seed: u64) -> DifficultyAgent<AlphaBetaAgent> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let search = AlphaBetaAgent::new(self.depth(), Heuristic::default())
+            .with_random_ties(Box::new(StdRng::seed_from_u64(rng.next_u64())));
+        DifficultyAgent::new(search, self.randomness(), Box::new(rng))
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Agent weakening a stronger one by playing a random legal move instead,
+/// with a fixed probability on every move
+pub struct DifficultyAgent<A> {
+    strong: A,
+    random: RandomAgent,
+    coin: Bernoulli,
+    rng: Box<dyn RngCore>,
+}
+
+impl<A> DifficultyAgent<A> {
+    /// Agent deferring to `strong` except for a share `randomness` of its moves.
+    /// The random moves and when to play them are both drawn from `rng`
+    ///
+    /// Panics if `randomness` is not between 0 and 1
+    pub fn new(strong: A, randomness: f64, mut rng: Box<dyn RngCore>) -> Self {
+        let coin = Bernoulli::new(randomness)
+            .expect("The probability of a random move has to be between 0 and 1");
+        let random = RandomAgent::seeded(rng.next_u64());
+        Self {
+            strong,
+            random,
+            coin,
+            rng,
+        }
+    }
+
+    /// Agent drawing its randomness from a generator seeded with `seed`
+    pub fn seeded(strong: A, randomness: f64, seed: u64) -> Self {
+        Self::new(strong, randomness, Box::new(StdRng::seed_from_u64(seed)))
+    }
+
+    pub fn strong(&self) -> &A {
+        &self.strong
+    }
+}
+
+impl<A, R, const W: usize, const H: usize> PlayerTrait<R> for DifficultyAgent<A>
+where
+    A: PlayerTrait<R>,
+    R: Ruleset<Board = Board<W, H>> + 'static,
+{
+    fn play(&mut self, game: &R) -> Move {
+        if self.coin.sample(&mut self.rng) {
+            self.random.play(game)
+        } else {
+            self.strong.play(game)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{player::Player, player_agent::agent::MockPlayerTrait, state::StandardGame};
+
+    use super::*;
+
+    fn strong(times: std::ops::RangeInclusive<usize>) -> MockPlayerTrait<StandardGame> {
+        let mut agent = MockPlayerTrait::new();
+        agent.expect_play().times(times).return_const(Move::Drop(0));
+        agent
+    }
+
+    #[test]
+    fn randomness_decides_who_plays() {
+        let game = StandardGame::default();
+        let mut agent = DifficultyAgent::seeded(strong(100..=100), 0., 1);
+        assert!((0..100).all(|_| agent.play(&game) == Move::Drop(0)));
+
+        let mut agent = DifficultyAgent::seeded(strong(0..=0), 1., 1);
+        (0..100).for_each(|_| {
+            agent.play(&game);
+        });
+
+        let mut agent = DifficultyAgent::seeded(strong(400..=600), 0.5, 1);
+        (0..1000).for_each(|_| {
+            agent.play(&game);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn randomness_is_a_probability() {
+        DifficultyAgent::seeded(strong(0..=0), 1.5, 1);
+    }
+
+    #[test]
+    fn presets() {
+        for levels in Difficulty::ALL.windows(2) {
+            assert!(levels[0].depth() < levels[1].depth());
+            assert!(levels[0].randomness() > levels[1].randomness());
+        }
+        assert_eq!(Difficulty::Expert.randomness(), 0.);
+        assert_eq!(Difficulty::Medium.to_string(), "medium");
+        assert_eq!(Difficulty::Hard.agent(3).strong().depth(), 5);
+    }
+
+    #[test]
+    fn seeded_games_repeat() {
+        let game_of = |seed| {
+            let mut red = Difficulty::Easy.agent(seed);
+            let mut yellow = Difficulty::Easy.agent(seed + 1);
+            let mut game = StandardGame::default();
+            let mut moves = Vec::new();
+            while !game.is_terminated() {
+                let mv = match game.to_move() {
+                    Player::Red => red.play(&game),
+                    _ => yellow.play(&game),
+                };
+                game.apply_move(game.to_move(), mv);
+                moves.push(mv);
+            }
+            moves
+        };
+        assert_eq!(game_of(5), game_of(5));
+        assert_ne!(game_of(5), game_of(7));
+    }
+}
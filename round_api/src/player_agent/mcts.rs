@@ -0,0 +1,390 @@
+use std::time::{Duration, Instant};
+
+use rand::prelude::*;
+
+use crate::{
+    board::{Board, TerminatedStatus},
+    player::Player,
+    rules::{Move, Ruleset},
+    state::GameState,
+};
+
+use super::agent::PlayerTrait;
+
+/// How long the agent searches before every move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    /// Number of playouts, each adding a node to the tree
+    Iterations(usize),
+    /// Wall-clock time, at least one playout is always run
+    Time(Duration),
+}
+
+/// Node of the search tree, stored in the arena of its [`Tree`]
+struct Node<R> {
+    state: R,
+    /// Move leading to the node from its parent, `None` at the root
+    mv: Option<Move>,
+    /// Player who made `mv`, the one rewarded by the results of the node
+    mover: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Legal moves not expanded into children yet
+    untried: Vec<Move>,
+    visits: u32,
+    /// Sum of the rewards of `mover` over the visits
+    reward: f64,
+}
+
+impl<R: Ruleset> Node<R> {
+    fn new(state: R, mv: Option<Move>, mover: Player, parent: Option<usize>) -> Self {
+        let untried = state.legal_moves();
+        Self {
+            state,
+            mv,
+            mover,
+            parent,
+            children: Vec::new(),
+            untried,
+            visits: 0,
+            reward: 0.,
+        }
+    }
+}
+
+/// Search tree, the root being the first node
+struct Tree<R> {
+    nodes: Vec<Node<R>>,
+}
+
+impl<R: Ruleset + PartialEq> Tree<R> {
+    fn new(state: R) -> Self {
+        // Nobody moved into the root, its own statistics are never used
+        let mover = state.to_move();
+        Self {
+            nodes: vec![Node::new(state, None, mover, None)],
+        }
+    }
+
+    /// Subtree of the node holding `state` among the root and its descendants
+    /// down to `depth` plies, re-rooted so it can be searched further
+    fn reroot(self, state: &R, depth: usize) -> Option<Self> {
+        let mut layer = vec![0];
+        for _ in 0..=depth {
+            if let Some(&found) = layer.iter().find(|&&i| self.nodes[i].state == *state) {
+                return Some(self.subtree(found));
+            }
+            layer = layer
+                .iter()
+                .flat_map(|&i| self.nodes[i].children.iter().copied())
+                .collect();
+        }
+        None
+    }
+
+    fn subtree(self, root: usize) -> Self {
+        // Kept nodes in breadth-first order, so parents come before their children
+        let mut order = vec![root];
+        let mut next = 0;
+        while next < order.len() {
+            order.extend(self.nodes[order[next]].children.iter().copied());
+            next += 1;
+        }
+        let mut new_index = vec![None; self.nodes.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = Some(new);
+        }
+        let mut old_nodes = self.nodes.into_iter().map(Some).collect::<Vec<_>>();
+        let nodes = order
+            .iter()
+            .map(|&old| {
+                let mut node = old_nodes[old].take().expect("Nodes are kept once");
+                node.parent = node.parent.and_then(|parent| new_index[parent]);
+                for child in &mut node.children {
+                    *child = new_index[*child].expect("Children are kept");
+                }
+                node
+            })
+            .collect();
+        Self { nodes }
+    }
+}
+
+/// Monte Carlo tree search with UCB1 selection and uniformly random playouts.
+/// It needs no knowledge of the game beyond its rules, and works for every variant
+/// and any number of players, a player being rewarded 1 for a win and ½ for a draw.
+///
+/// The tree searched for a move is kept for the next one,
+/// when the new position was reached from it
+pub struct MctsAgent<R = GameState> {
+    budget: Budget,
+    exploration: f64,
+    reuse_tree: bool,
+    rng: Box<dyn RngCore>,
+    tree: Option<Tree<R>>,
+}
+
+impl<R: Ruleset + PartialEq> MctsAgent<R> {
+    pub fn new(budget: Budget, rng: Box<dyn RngCore>) -> Self {
+        Self {
+            budget,
+            exploration: std::f64::consts::SQRT_2,
+            reuse_tree: true,
+            rng,
+            tree: None,
+        }
+    }
+
+    /// Agent drawing its playouts from a generator seeded with `seed`
+    pub fn seeded(budget: Budget, seed: u64) -> Self {
+        Self::new(budget, Box::new(StdRng::seed_from_u64(seed)))
+    }
+
+    /// Weight of the exploration term of UCB1, `√2` by default
+    pub fn with_exploration(self, exploration: f64) -> Self {
+        Self {
+            exploration,
+            ..self
+        }
+    }
+
+    /// Keep the tree between moves, enabled by default
+    pub fn with_tree_reuse(self, reuse_tree: bool) -> Self {
+        Self { reuse_tree, ..self }
+    }
+
+    pub fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    pub fn exploration(&self) -> f64 {
+        self.exploration
+    }
+
+    /// Playouts gone through the root of the tree kept from the last move
+    pub fn root_visits(&self) -> u32 {
+        self.tree.as_ref().map_or(0, |tree| tree.nodes[0].visits)
+    }
+
+    /// Visits and mean reward of every move searched from the root of the last tree
+    pub fn statistics(&self) -> Vec<(Move, u32, f64)> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        tree.nodes[0]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &tree.nodes[child];
+                let mean = node.reward / node.visits.max(1) as f64;
+                (node.mv.expect("Children have a move"), node.visits, mean)
+            })
+            .collect()
+    }
+
+    /// Tree to search from `game`, the one from the last move if it leads there.
+    /// The position can be up to a full round of every player away
+    fn take_tree(&mut self, game: &R) -> Tree<R> {
+        match self.tree.take() {
+            Some(tree) if self.reuse_tree => tree.reroot(game, crate::MAX_PLAYERS),
+            _ => None,
+        }
+        .unwrap_or_else(|| Tree::new(game.clone()))
+    }
+
+    fn ucb(&self, node: &Node<R>, parent_visits: u32) -> f64 {
+        if node.visits == 0 {
+            return f64::INFINITY;
+        }
+        let visits = node.visits as f64;
+        node.reward / visits + self.exploration * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+
+    /// Runs one playout, adding at most one node to the tree
+    fn iterate(&mut self, tree: &mut Tree<R>) {
+        let mut index = 0;
+        // Selection
+        while tree.nodes[index].untried.is_empty() && !tree.nodes[index].children.is_empty() {
+            let node = &tree.nodes[index];
+            index = *node
+                .children
+                .iter()
+                .max_by(|a, b| {
+                    let a = self.ucb(&tree.nodes[**a], node.visits);
+                    let b = self.ucb(&tree.nodes[**b], node.visits);
+                    a.total_cmp(&b)
+                })
+                .expect("Children were checked");
+        }
+        // Expansion
+        if !tree.nodes[index].untried.is_empty() {
+            let node = &mut tree.nodes[index];
+            let mv = node
+                .untried
+                .swap_remove(self.rng.gen_range(0..node.untried.len()));
+            let mut state = node.state.clone();
+            let mover = state.to_move();
+            state.apply_move(mover, mv);
+            let child = tree.nodes.len();
+            tree.nodes[index].children.push(child);
+            tree.nodes
+                .push(Node::new(state, Some(mv), mover, Some(index)));
+            index = child;
+        }
+        // Simulation
+        let result = self.playout(tree.nodes[index].state.clone());
+        // Backpropagation
+        let mut current = Some(index);
+        while let Some(i) = current {
+            let node = &mut tree.nodes[i];
+            node.visits += 1;
+            node.reward += match result {
+                Some(TerminatedStatus::Win(winner)) if winner == node.mover => 1.,
+                Some(TerminatedStatus::Win(_)) => 0.,
+                // Games stuck without a legal move count as draws
+                Some(TerminatedStatus::Draw) | None => 0.5,
+            };
+            current = node.parent;
+        }
+    }
+
+    /// Plays random moves until the game is over
+    fn playout(&mut self, mut state: R) -> Option<TerminatedStatus> {
+        while state.result().is_none() {
+            let player = state.to_move();
+            let mv = *state.legal_moves().choose(&mut self.rng)?;
+            state.apply_move(player, mv);
+        }
+        state.result()
+    }
+}
+
+/// Plays the most visited move of the root once the budget is spent
+///
+/// Panics if the game has no legal move left
+impl<R, const W: usize, const H: usize> PlayerTrait<R> for MctsAgent<R>
+where
+    R: Ruleset<Board = Board<W, H>> + PartialEq + 'static,
+{
+    fn play(&mut self, game: &R) -> Move {
+        assert!(
+            !game.legal_moves().is_empty(),
+            "The game has no legal move left"
+        );
+        let mut tree = self.take_tree(game);
+        match self.budget {
+            Budget::Iterations(iterations) => {
+                for _ in 0..iterations.max(1) {
+                    self.iterate(&mut tree);
+                }
+            }
+            Budget::Time(duration) => {
+                let start = Instant::now();
+                self.iterate(&mut tree);
+                while start.elapsed() < duration {
+                    self.iterate(&mut tree);
+                }
+            }
+        }
+        let best = tree.nodes[0]
+            .children
+            .iter()
+            .map(|&child| &tree.nodes[child])
+            .max_by_key(|node| node.visits)
+            .and_then(|node| node.mv)
+            .expect("The root has been expanded");
+        self.tree = Some(tree);
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::StandardGame;
+
+    use super::*;
+
+    #[test]
+    fn takes_immediate_wins() {
+        let mut agent = MctsAgent::seeded(Budget::Iterations(1000), 1);
+        assert_eq!(
+            agent.play(&StandardGame::from_moves("121212").unwrap()),
+            Move::Drop(0)
+        );
+        let mut agent = MctsAgent::seeded(Budget::Iterations(1000), 2);
+        let mv = agent.play(&StandardGame::from_moves("445566").unwrap());
+        assert!(matches!(mv, Move::Drop(2) | Move::Drop(6)));
+    }
+
+    #[test]
+    fn blocks_immediate_losses() {
+        for seed in 0..3 {
+            let mut agent = MctsAgent::seeded(Budget::Iterations(2000), seed);
+            assert_eq!(
+                agent.play(&StandardGame::from_moves("12121").unwrap()),
+                Move::Drop(0)
+            );
+        }
+    }
+
+    #[test]
+    fn statistics_add_up() {
+        let mut agent = MctsAgent::seeded(Budget::Iterations(500), 3);
+        assert!(agent.statistics().is_empty());
+        agent.play(&StandardGame::default());
+        let statistics = agent.statistics();
+        assert_eq!(statistics.len(), 7);
+        let visits = statistics.iter().map(|(_, visits, _)| visits).sum::<u32>();
+        assert_eq!(visits, 500);
+        assert_eq!(agent.root_visits(), 500);
+        assert!(statistics
+            .iter()
+            .all(|(_, _, mean)| (0. ..=1.).contains(mean)));
+    }
+
+    #[test]
+    fn trees_are_reused() {
+        let mut agent = MctsAgent::seeded(Budget::Iterations(300), 4);
+        let mut game = StandardGame::default();
+        let mv = agent.play(&game);
+        game.apply_move(Player::Red, mv);
+        game.apply_move(Player::Yellow, Move::Drop(0));
+        // The tree already holds playouts through the new position
+        agent.play(&game);
+        assert!(agent.root_visits() > 300);
+
+        let mut fresh = MctsAgent::seeded(Budget::Iterations(300), 4).with_tree_reuse(false);
+        fresh.play(&StandardGame::default());
+        fresh.play(&game);
+        assert_eq!(fresh.root_visits(), 300);
+    }
+
+    #[test]
+    fn unrelated_positions_start_a_new_tree() {
+        let mut agent = MctsAgent::seeded(Budget::Iterations(100), 5);
+        agent.play(&StandardGame::from_moves("4444").unwrap());
+        agent.play(&StandardGame::from_moves("1111").unwrap());
+        assert_eq!(agent.root_visits(), 100);
+    }
+
+    #[test]
+    fn time_budget() {
+        let mut agent = MctsAgent::seeded(Budget::Time(Duration::from_millis(50)), 6);
+        let start = Instant::now();
+        agent.play(&StandardGame::default());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(agent.root_visits() > 0);
+    }
+
+    #[test]
+    fn plays_other_rulesets() {
+        use crate::rules::PopOut;
+
+        let mut game = PopOut::<7, 6>::default();
+        for col in [1, 1, 2, 2, 3, 3] {
+            game.apply_move(game.to_move(), Move::Drop(col));
+        }
+        let mut agent = MctsAgent::seeded(Budget::Iterations(1000), 7).with_exploration(1.);
+        assert!(matches!(agent.play(&game), Move::Drop(0) | Move::Drop(4)));
+    }
+}
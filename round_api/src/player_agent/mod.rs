@@ -1,3 +1,7 @@
 pub mod agent;
-mod rand_agent;
+pub mod alpha_beta;
+pub mod difficulty;
+pub mod mcts;
+pub mod puct;
+pub mod rand_agent;
 mod user_agent;
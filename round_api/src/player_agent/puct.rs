@@ -0,0 +1,453 @@
+use std::time::Instant;
+
+use rand::{distributions::WeightedIndex, prelude::*};
+use rand_distr::Dirichlet;
+
+use crate::{
+    board::{Board, TerminatedStatus},
+    network::{Network, Uniform},
+    player::Player,
+    rules::{Move, Ruleset},
+};
+
+use super::{agent::PlayerTrait, mcts::Budget};
+
+/// Noise mixed into the priors of the root, so self-play games explore other openings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirichletNoise {
+    /// Concentration of the distribution, lower values put the noise on fewer moves
+    pub alpha: f64,
+    /// Share of the noise in the priors of the root
+    pub epsilon: f64,
+}
+
+impl Default for DirichletNoise {
+    fn default() -> Self {
+        Self {
+            alpha: 1.,
+            epsilon: 0.25,
+        }
+    }
+}
+
+/// Node of the search tree, its children being expanded all at once
+struct Node<R> {
+    state: R,
+    /// Move leading to the node from its parent, `None` at the root
+    mv: Option<Move>,
+    /// Player who made `mv`, the one whose values are summed in the node
+    mover: Player,
+    prior: f64,
+    children: Vec<usize>,
+    expanded: bool,
+    visits: u32,
+    value: f64,
+}
+
+impl<R> Node<R> {
+    fn new(state: R, mv: Option<Move>, mover: Player, prior: f64) -> Self {
+        Self {
+            state,
+            mv,
+            mover,
+            prior,
+            children: Vec::new(),
+            expanded: false,
+            visits: 0,
+            value: 0.,
+        }
+    }
+
+    /// Mean value for the player who moved into the node, 0 before any visit
+    fn mean(&self) -> f64 {
+        if self.visits == 0 {
+            0.
+        } else {
+            self.value / self.visits as f64
+        }
+    }
+}
+
+/// Tree search guided by a [`Network`], as in AlphaZero.
+/// Moves are picked with the PUCT formula, weighing the mean value of a move
+/// against its prior, and positions are valued by the network instead of playouts.
+/// Games are assumed to have two players.
+///
+/// A budget of [`Budget::Iterations`] counts simulations,
+/// each valuing one new position with the network
+pub struct PuctAgent<N = Uniform> {
+    network: N,
+    budget: Budget,
+    exploration: f64,
+    noise: Option<DirichletNoise>,
+    temperature: f64,
+    rng: Box<dyn RngCore>,
+    distribution: Vec<(Move, f32)>,
+    /// Width of the board of the last search
+    width: usize,
+}
+
+impl<N> PuctAgent<N> {
+    pub fn new(network: N, budget: Budget, rng: Box<dyn RngCore>) -> Self {
+        Self {
+            network,
+            budget,
+            exploration: 1.5,
+            noise: None,
+            temperature: 0.,
+            rng,
+            distribution: Vec::new(),
+            width: 0,
+        }
+    }
+
+    /// Agent drawing its noise and moves from a generator seeded with `seed`
+    pub fn seeded(network: N, budget: Budget, seed: u64) -> Self {
+        Self::new(network, budget, Box::new(StdRng::seed_from_u64(seed)))
+    }
+
+    /// Weight of the priors against the mean values, 1.5 by default
+    pub fn with_exploration(self, exploration: f64) -> Self {
+        Self {
+            exploration,
+            ..self
+        }
+    }
+
+    /// Mixes Dirichlet noise into the priors of the root before every search
+    ///
+    /// Panics unless `alpha` is positive
+    pub fn with_noise(self, noise: DirichletNoise) -> Self {
+        assert!(noise.alpha > 0., "The concentration has to be positive");
+        Self {
+            noise: Some(noise),
+            ..self
+        }
+    }
+
+    /// Plays moves with a probability proportional to `visits^(1 / temperature)`.
+    /// The default of 0 always plays the most visited move
+    ///
+    /// Panics if `temperature` is negative
+    pub fn with_temperature(self, temperature: f64) -> Self {
+        assert!(temperature >= 0., "The temperature can not be negative");
+        Self {
+            temperature,
+            ..self
+        }
+    }
+
+    pub fn network(&self) -> &N {
+        &self.network
+    }
+
+    pub fn network_mut(&mut self) -> &mut N {
+        &mut self.network
+    }
+
+    /// Share of the visits of every legal move in the last search, adding up to one
+    pub fn visit_distribution(&self) -> &[(Move, f32)] {
+        &self.distribution
+    }
+
+    /// Visit distribution of the last search as a training target,
+    /// the drops in every column of its board followed by the pops.
+    /// Empty before the first search
+    pub fn policy_target(&self) -> Vec<f32> {
+        let mut target = vec![0.; 2 * self.width];
+        for (mv, share) in &self.distribution {
+            match mv {
+                Move::Drop(col) => target[*col as usize] = *share,
+                Move::Pop(col) => target[self.width + *col as usize] = *share,
+            }
+        }
+        target
+    }
+
+    /// Move picked from the last visit distribution according to the temperature
+    fn select(&mut self) -> Move {
+        let most_visited = || {
+            self.distribution
+                .iter()
+                .copied()
+                .reduce(|best, other| if other.1 > best.1 { other } else { best })
+                .map(|(mv, _)| mv)
+        };
+        let best = most_visited().expect("The search found no legal move");
+        if self.temperature == 0. {
+            return best;
+        }
+        let weights = self
+            .distribution
+            .iter()
+            .map(|(_, share)| (*share as f64).powf(1. / self.temperature));
+        // Very low temperatures can underflow every weight
+        match WeightedIndex::new(weights) {
+            Ok(index) => self.distribution[index.sample(&mut self.rng)].0,
+            Err(_) => best,
+        }
+    }
+
+    /// Searches `game` within the budget, returning the visit distribution of its legal moves
+    ///
+    /// Panics if the game has no legal move left
+    pub fn search<R, const W: usize, const H: usize>(&mut self, game: &R) -> &[(Move, f32)]
+    where
+        R: Ruleset<Board = Board<W, H>> + 'static,
+        N: Network<R>,
+    {
+        assert!(
+            !game.legal_moves().is_empty(),
+            "The game has no legal move left"
+        );
+        let mut tree = vec![Node::new(game.clone(), None, game.to_move(), 1.)];
+        self.expand(&mut tree, 0);
+        if let Some(noise) = self.noise {
+            let children = tree[0].children.clone();
+            // A single legal move leaves nothing to explore
+            if let Ok(dirichlet) = Dirichlet::new_with_size(noise.alpha, children.len()) {
+                let samples = dirichlet.sample(&mut self.rng);
+                for (child, sample) in children.into_iter().zip(samples) {
+                    let prior = &mut tree[child].prior;
+                    *prior = (1. - noise.epsilon) * *prior + noise.epsilon * sample;
+                }
+            }
+        }
+
+        match self.budget {
+            Budget::Iterations(iterations) => {
+                for _ in 0..iterations.max(1) {
+                    self.simulate(&mut tree);
+                }
+            }
+            Budget::Time(duration) => {
+                let start = Instant::now();
+                self.simulate(&mut tree);
+                while start.elapsed() < duration {
+                    self.simulate(&mut tree);
+                }
+            }
+        }
+
+        let total = tree[0]
+            .children
+            .iter()
+            .map(|&child| tree[child].visits)
+            .sum::<u32>()
+            .max(1) as f32;
+        self.distribution = tree[0]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &tree[child];
+                let mv = node.mv.expect("Children have a move");
+                (mv, node.visits as f32 / total)
+            })
+            .collect();
+        self.width = W;
+        &self.distribution
+    }
+
+    /// Adds every legal move of the node as a child, weighted by the priors of the network.
+    /// Returns the value of the node for the player to move
+    fn expand<R>(&mut self, tree: &mut Vec<Node<R>>, index: usize) -> f64
+    where
+        R: Ruleset + 'static,
+        N: Network<R>,
+    {
+        let state = tree[index].state.clone();
+        let mover = state.to_move();
+        let moves = state.legal_moves();
+        let prediction = self.network.predict(&state);
+        assert_eq!(
+            prediction.priors.len(),
+            moves.len(),
+            "The network has to give a prior for every legal move"
+        );
+        let priors = prediction.priors.iter().map(|prior| prior.max(0.) as f64);
+        let sum = priors.clone().sum::<f64>();
+        for (mv, prior) in moves.iter().zip(priors) {
+            let prior = if sum > 0. {
+                prior / sum
+            } else {
+                1. / moves.len() as f64
+            };
+            let mut child = state.clone();
+            child.apply_move(mover, *mv);
+            let index_of_child = tree.len();
+            tree[index].children.push(index_of_child);
+            tree.push(Node::new(child, Some(*mv), mover, prior));
+        }
+        tree[index].expanded = true;
+        prediction.value.clamp(-1., 1.) as f64
+    }
+
+    /// Child of the node with the highest PUCT score
+    fn best_child<R>(&self, tree: &[Node<R>], index: usize) -> usize {
+        let node = &tree[index];
+        let explore = self.exploration * (node.visits.max(1) as f64).sqrt();
+        let score =
+            |child: &Node<R>| child.mean() + explore * child.prior / (1. + child.visits as f64);
+        *node
+            .children
+            .iter()
+            .max_by(|a, b| score(&tree[**a]).total_cmp(&score(&tree[**b])))
+            .expect("Expanded nodes have children")
+    }
+
+    /// Walks down to a new position, values it and backs the value up the path
+    fn simulate<R>(&mut self, tree: &mut Vec<Node<R>>)
+    where
+        R: Ruleset + 'static,
+        N: Network<R>,
+    {
+        let mut path = vec![0];
+        let mut index = 0;
+        while tree[index].expanded && !tree[index].children.is_empty() {
+            index = self.best_child(tree, index);
+            path.push(index);
+        }
+        let to_move = tree[index].state.to_move();
+        let value = match tree[index].state.result() {
+            Some(TerminatedStatus::Win(winner)) if winner == to_move => 1.,
+            Some(TerminatedStatus::Win(_)) => -1.,
+            Some(TerminatedStatus::Draw) => 0.,
+            None => self.expand(tree, index),
+        };
+        for i in path {
+            let node = &mut tree[i];
+            node.visits += 1;
+            node.value += if node.mover == to_move { value } else { -value };
+        }
+    }
+}
+
+/// Plays a move of the visit distribution, chosen according to the temperature
+///
+/// Panics if the game has no legal move left
+impl<N, R, const W: usize, const H: usize> PlayerTrait<R> for PuctAgent<N>
+where
+    N: Network<R>,
+    R: Ruleset<Board = Board<W, H>> + 'static,
+{
+    fn play(&mut self, game: &R) -> Move {
+        self.search(game);
+        self.select()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        evaluation::Heuristic,
+        network::{EvaluatorNetwork, MockNetwork, Prediction},
+        state::StandardGame,
+    };
+
+    use super::*;
+
+    #[test]
+    fn takes_immediate_wins() {
+        let mut agent = PuctAgent::seeded(Uniform, Budget::Iterations(200), 1);
+        assert_eq!(
+            agent.play(&StandardGame::from_moves("121212").unwrap()),
+            Move::Drop(0)
+        );
+        let mv = agent.play(&StandardGame::from_moves("445566").unwrap());
+        assert!(matches!(mv, Move::Drop(2) | Move::Drop(6)));
+    }
+
+    #[test]
+    fn blocks_immediate_losses() {
+        let network = EvaluatorNetwork::new(Heuristic::default(), 20.);
+        let mut agent = PuctAgent::seeded(network, Budget::Iterations(400), 2);
+        assert_eq!(
+            agent.play(&StandardGame::from_moves("12121").unwrap()),
+            Move::Drop(0)
+        );
+        let mut agent = PuctAgent::seeded(Uniform, Budget::Iterations(400), 2);
+        assert_eq!(
+            agent.play(&StandardGame::from_moves("12121").unwrap()),
+            Move::Drop(0)
+        );
+    }
+
+    #[test]
+    fn visits_make_a_distribution() {
+        let mut agent = PuctAgent::seeded(Uniform, Budget::Iterations(100), 3);
+        assert!(agent.policy_target().is_empty());
+        let mut game = StandardGame::default();
+        for _ in 0..6 {
+            game.apply(game.to_move(), 3);
+        }
+        let distribution = agent.search(&game).to_vec();
+        assert_eq!(distribution.len(), 6);
+        assert!(distribution.iter().all(|(mv, _)| *mv != Move::Drop(3)));
+        let total = distribution.iter().map(|(_, share)| share).sum::<f32>();
+        assert!((total - 1.).abs() < 1e-5);
+
+        let target = agent.policy_target();
+        assert_eq!(target.len(), 14);
+        assert_eq!(target[3], 0.);
+        assert!((target.iter().sum::<f32>() - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn priors_guide_the_search() {
+        let mut network = MockNetwork::<StandardGame>::new();
+        network.expect_predict().returning(|game| {
+            let moves = game.legal_moves();
+            let priors = moves
+                .iter()
+                .map(|mv| if *mv == Move::Drop(5) { 10. } else { 1. })
+                .collect();
+            Prediction { priors, value: 0. }
+        });
+        let mut agent = PuctAgent::seeded(network, Budget::Iterations(50), 4);
+        agent.search(&StandardGame::default());
+        let (mv, share) = agent
+            .visit_distribution()
+            .iter()
+            .copied()
+            .reduce(|a, b| if b.1 > a.1 { b } else { a })
+            .unwrap();
+        assert_eq!(mv, Move::Drop(5));
+        assert!(share > 0.5);
+    }
+
+    #[test]
+    fn temperature() {
+        let game = StandardGame::default();
+        let moves = |temperature| {
+            let mut agent =
+                PuctAgent::seeded(Uniform, Budget::Iterations(50), 5).with_temperature(temperature);
+            (0..30)
+                .map(|_| agent.play(&game))
+                .collect::<HashSet<Move>>()
+        };
+        assert_eq!(moves(0.).len(), 1);
+        assert!(moves(1.).len() > 1);
+    }
+
+    #[test]
+    fn noise_changes_the_root() {
+        let game = StandardGame::default();
+        let search = |noise: Option<DirichletNoise>, seed| {
+            let agent = PuctAgent::seeded(Uniform, Budget::Iterations(100), seed);
+            let mut agent = match noise {
+                Some(noise) => agent.with_noise(noise),
+                None => agent,
+            };
+            agent.search(&game).to_vec()
+        };
+        let noise = DirichletNoise {
+            alpha: 0.3,
+            epsilon: 0.5,
+        };
+        assert_eq!(search(None, 6), search(None, 7));
+        assert_eq!(search(Some(noise), 6), search(Some(noise), 6));
+        assert_ne!(search(Some(noise), 6), search(Some(noise), 7));
+    }
+}
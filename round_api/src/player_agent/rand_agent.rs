@@ -1,21 +1,85 @@
-use rand::prelude::*;
+use rand::{distributions::WeightedIndex, prelude::*};
 
-use crate::board::Board;
+use crate::{
+    board::{Board, Col},
+    rules::{Move, Ruleset},
+};
 
 use super::agent::PlayerTrait;
+
+/// How likely each column is to be picked, relative to the others
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnBias {
+    /// Every legal move is as likely
+    Uniform,
+    /// Columns are weighted by `exp(-strength * distance)`,
+    /// the distance being counted in columns from the middle of the board
+    Center(f64),
+    /// Weight of every column, missing columns have no weight
+    /// and negative or NaN weights count as none
+    Weights(Vec<f64>),
+}
+
+/// Agent playing legal moves at random, a baseline opponent and an easy bot.
+/// Seeded agents always play the same games against the same moves
 pub struct RandomAgent {
     rng: Box<dyn RngCore>,
+    bias: ColumnBias,
 }
 
 impl RandomAgent {
-    fn new(rng: Box<dyn RngCore>) -> Self {
-        Self { rng }
+    pub fn new(rng: Box<dyn RngCore>) -> Self {
+        Self {
+            rng,
+            bias: ColumnBias::Uniform,
+        }
+    }
+
+    /// Agent drawing its moves from a generator seeded with `seed`
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(Box::new(StdRng::seed_from_u64(seed)))
+    }
+
+    pub fn with_bias(self, bias: ColumnBias) -> Self {
+        Self { bias, ..self }
+    }
+
+    pub fn bias(&self) -> &ColumnBias {
+        &self.bias
+    }
+
+    /// Weight of `col`, never negative nor NaN so that it can be sampled from
+    fn weight<const W: usize>(&self, col: Col) -> f64 {
+        let weight = match &self.bias {
+            ColumnBias::Uniform => 1.,
+            ColumnBias::Center(strength) => {
+                let distance = (col as f64 - (W - 1) as f64 / 2.).abs();
+                (-strength * distance).exp()
+            }
+            ColumnBias::Weights(weights) => weights.get(col as usize).copied().unwrap_or(0.),
+        };
+        // `max` returns the other argument when one is NaN
+        weight.max(0.)
     }
 }
 
-impl PlayerTrait for RandomAgent {
-    fn play(&mut self, _board: &Board) -> crate::board::Col {
-        self.rng.as_mut().gen_range(0..8)
+/// Picks one of the legal moves of the game, moves sharing the weight of their column.
+/// When no legal move has a positive weight they are all as likely
+///
+/// Panics if the game has no legal move left
+impl<R, const W: usize, const H: usize> PlayerTrait<R> for RandomAgent
+where
+    R: Ruleset<Board = Board<W, H>> + 'static,
+{
+    fn play(&mut self, game: &R) -> Move {
+        let moves = game.legal_moves();
+        let weights = moves.iter().map(|mv| self.weight::<W>(mv.col()));
+        match WeightedIndex::new(weights) {
+            Ok(index) => moves[index.sample(&mut self.rng)],
+            Err(_) => *moves
+                .choose(&mut self.rng)
+                .expect("The game has no legal move left"),
+        }
     }
 }
 
@@ -23,30 +87,121 @@ impl PlayerTrait for RandomAgent {
 mod tests {
     use approx::Relative;
 
+    use crate::{player::Player, rules::PopOut, state::GameState};
+
     use super::*;
+
+    /// Share of the moves played in every column
+    fn frequencies<R, const W: usize, const H: usize>(
+        agent: &mut RandomAgent,
+        game: &R,
+        n: usize,
+    ) -> [f64; W]
+    where
+        R: Ruleset<Board = Board<W, H>> + 'static,
+    {
+        (0..n)
+            .fold([0usize; W], |mut acc, _| {
+                acc[agent.play(game).col() as usize] += 1;
+                acc
+            })
+            .map(|count| count as f64 / n as f64)
+    }
+
     #[test]
     fn test_creation() {
         let seedable = Box::new(StdRng::seed_from_u64(32));
         let _agent = RandomAgent::new(seedable);
         let random_from_thread = Box::new(thread_rng());
         let _agent = RandomAgent::new(random_from_thread);
+        let agent = RandomAgent::seeded(32).with_bias(ColumnBias::Center(1.));
+        assert_eq!(agent.bias(), &ColumnBias::Center(1.));
     }
 
     #[test]
     fn test_true_random() {
         const N: usize = 1_000_000usize;
-        const ONE_EIGHT: f64 = 1. / 8.;
-
-        let mut rng = thread_rng();
-        let board = Board::from_rng(&mut rng);
-        let mut agent = RandomAgent::new(Box::new(rng));
-        let arr = (0..N).fold([0usize; 8], |mut acc, _numb| {
-            acc[agent.play(&board) as usize] += 1;
-            acc
-        });
+
+        let mut game: GameState = GameState::default();
+        // Full columns can not be played
+        for col in [2, 6, 7] {
+            for _ in 0..8 {
+                game.apply(game.to_move(), col);
+            }
+        }
+        let valid = game.board().valid_moves();
+        assert_eq!(valid.iter().filter(|valid| **valid).count(), 5);
+        let mut agent = RandomAgent::new(Box::new(thread_rng()));
+        let arr = frequencies(&mut agent, &game, N);
+        assert!(arr.into_iter().zip(valid).all(|(el, valid)| if valid {
+            Relative::default().epsilon(0.01).eq(&el, &(1. / 5.))
+        } else {
+            el == 0.
+        }));
+    }
+
+    #[test]
+    fn seeded_agents_repeat_their_moves() {
+        let game = GameState::<7, 6>::default();
+        let moves = |seed| {
+            let mut agent = RandomAgent::seeded(seed);
+            (0..20).map(|_| agent.play(&game)).collect::<Vec<_>>()
+        };
+        assert_eq!(moves(3), moves(3));
+        assert_ne!(moves(3), moves(4));
+    }
+
+    #[test]
+    fn center_bias() {
+        let game = GameState::<7, 6>::default();
+        let mut agent = RandomAgent::seeded(5).with_bias(ColumnBias::Center(1.));
+        let arr = frequencies(&mut agent, &game, 100_000);
+        assert!(arr[3] > arr[2] && arr[2] > arr[1] && arr[1] > arr[0]);
+        assert!(Relative::default().epsilon(0.05).eq(&arr[0], &arr[6]));
+
+        let mut agent = RandomAgent::seeded(5).with_bias(ColumnBias::Center(0.));
+        let arr = frequencies(&mut agent, &game, 100_000);
         assert!(arr
             .into_iter()
-            .map(|el| el as f64 / N as f64)
-            .all(|el| Relative::default().epsilon(0.01).eq(&el, &ONE_EIGHT)));
+            .all(|el| Relative::default().epsilon(0.05).eq(&el, &(1. / 7.))));
+    }
+
+    #[test]
+    fn column_weights() {
+        let mut game = GameState::<7, 6>::default();
+        let mut agent = RandomAgent::seeded(6).with_bias(ColumnBias::Weights(vec![0., 1., 3.]));
+        let arr = frequencies(&mut agent, &game, 100_000);
+        assert!(Relative::default().epsilon(0.05).eq(&arr[2], &0.75));
+        assert_eq!(arr[0] + arr[3..].iter().sum::<f64>(), 0.);
+
+        // Once the weighted columns are full any legal move can be picked
+        for col in [1, 2] {
+            for _ in 0..6 {
+                game.apply(game.to_move(), col);
+            }
+        }
+        let arr = frequencies(&mut agent, &game, 10_000);
+        assert_eq!(arr[1] + arr[2], 0.);
+        assert!(arr[0] > 0.);
+    }
+
+    #[test]
+    fn invalid_weights_count_as_none() {
+        let game = GameState::<7, 6>::default();
+        let mut agent =
+            RandomAgent::seeded(7).with_bias(ColumnBias::Weights(vec![-1., 1., f64::NAN, 1.]));
+        let arr = frequencies(&mut agent, &game, 10_000);
+        assert_eq!(arr[0] + arr[2], 0.);
+        assert!(Relative::default().epsilon(0.05).eq(&arr[1], &0.5));
+        assert!(Relative::default().epsilon(0.05).eq(&arr[3], &0.5));
+    }
+
+    #[test]
+    fn pops_are_played() {
+        let mut game = PopOut::<7, 6>::default();
+        game.apply_move(Player::Red, Move::Drop(3));
+        game.apply_move(Player::Yellow, Move::Drop(3));
+        let mut agent = RandomAgent::seeded(8);
+        assert!((0..200).any(|_| agent.play(&game) == Move::Pop(3)));
     }
 }
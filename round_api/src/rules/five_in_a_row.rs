@@ -0,0 +1,118 @@
+//! Five-in-a-Row, Connect 4 on a board widened by a full border column on each side,
+//! where five pieces in a row are needed to win
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{to_position, Board, Col, GamePlay, Row, TerminatedStatus},
+    player::Player,
+    state::GameState,
+};
+
+use super::{Move, Ruleset};
+
+/// Five-in-a-Row, played on the classic board with a full border column
+/// on each side, filled with alternating pieces. Five pieces in a row are needed to win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiveInARow(GameState<9, 6>);
+
+impl Default for FiveInARow {
+    fn default() -> Self {
+        let mut pieces = [0; 2];
+        for row in 0..6 {
+            // Red at the bottom of the left column, yellow at the bottom of the right one
+            let (left, right) = if row % 2 == 0 {
+                (Player::Red, Player::Yellow)
+            } else {
+                (Player::Yellow, Player::Red)
+            };
+            pieces[left as usize] |= 1 << to_position::<6>(0, row as Row);
+            pieces[right as usize] |= 1 << to_position::<6>(8, row as Row);
+        }
+        Self(GameState::from_board(Board::from_pieces(&pieces, 5)))
+    }
+}
+
+impl FiveInARow {
+    /// Border columns that are full from the start
+    pub const BORDERS: [Col; 2] = [0, 8];
+
+    pub fn state(&self) -> &GameState<9, 6> {
+        &self.0
+    }
+
+    /// Number of moves played so far
+    pub fn ply(&self) -> usize {
+        self.0.ply()
+    }
+}
+
+impl Ruleset for FiveInARow {
+    type Board = Board<9, 6>;
+
+    fn board(&self) -> &Board<9, 6> {
+        self.0.board()
+    }
+
+    fn to_move(&self) -> Player {
+        self.0.to_move()
+    }
+
+    fn result(&self) -> Option<TerminatedStatus> {
+        self.0.result()
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        self.0.legal_moves()
+    }
+
+    fn apply_move(&mut self, player: Player, mv: Move) -> GamePlay {
+        self.0.apply_move(player, mv)
+    }
+
+    fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus> {
+        self.0.forfeit(player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::IllegalMove;
+
+    use super::*;
+
+    #[test]
+    fn borders_are_full() {
+        let game = FiveInARow::default();
+        assert_eq!(game.board().win_length(), 5);
+        assert_eq!(game.to_move(), Player::Red);
+        assert_eq!(game.result(), None);
+        assert_eq!(
+            game.legal_moves(),
+            (1..8).map(Move::Drop).collect::<Vec<_>>()
+        );
+        let mut game = game;
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Drop(FiveInARow::BORDERS[0])),
+            GamePlay::InvalidBoard(IllegalMove::StackIsFull)
+        );
+        assert!(game.board().history().is_empty());
+    }
+
+    #[test]
+    fn five_pieces_win() {
+        let mut game = FiveInARow::default();
+        // Red uses the bottom of the left border
+        for col in [1, 1, 2, 2, 3, 3] {
+            assert_eq!(
+                game.apply_move(game.to_move(), Move::Drop(col)),
+                GamePlay::ValidPlay
+            );
+        }
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Drop(4)),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert_eq!(game.ply(), 7);
+    }
+}
@@ -0,0 +1,170 @@
+//! Rules of the game and its variants.
+//! Every variant is a game state implementing [`Ruleset`],
+//! so agents and the game loop can be written once for all of them.
+//! Classic Connect 4 is played with [`GameState`]
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Col, GamePlay, IllegalMove, TerminatedStatus},
+    player::Player,
+    state::GameState,
+};
+
+mod five_in_a_row;
+mod pop_ten;
+mod popout;
+
+pub use five_in_a_row::FiveInARow;
+pub use pop_ten::{PopTen, PopTenPhase};
+pub use popout::PopOut;
+
+/// Move made by a player on its turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    /// Drops a piece at the top of the column
+    Drop(Col),
+    /// Removes the piece at the bottom of the column
+    Pop(Col),
+}
+
+impl Move {
+    pub fn col(self) -> Col {
+        match self {
+            Move::Drop(col) | Move::Pop(col) => col,
+        }
+    }
+}
+
+impl From<Col> for Move {
+    fn from(col: Col) -> Self {
+        Move::Drop(col)
+    }
+}
+
+/// State of a game played under a set of rules
+pub trait Ruleset: Clone {
+    /// Board the game is played on
+    type Board;
+
+    fn board(&self) -> &Self::Board;
+
+    /// Player expected to make the next move
+    fn to_move(&self) -> Player;
+
+    /// Outcome of the game, `None` while it is still going on
+    fn result(&self) -> Option<TerminatedStatus>;
+
+    fn is_terminated(&self) -> bool {
+        self.result().is_some()
+    }
+
+    /// Every move the player to move can make, empty once the game is over
+    fn legal_moves(&self) -> Vec<Move>;
+
+    /// Makes `mv` for `player`.
+    /// Illegal moves are refused and leave the state untouched
+    fn apply_move(&mut self, player: Player, mv: Move) -> GamePlay;
+
+    /// Takes `player` out of the game, the last player left winning it.
+    /// Returns the result once the game is over, left unchanged if it already was,
+    /// and `None` while the other players play on
+    fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus>;
+}
+
+/// Classic Connect 4, where pieces can only be dropped
+impl<const W: usize, const H: usize> Ruleset for GameState<W, H> {
+    type Board = Board<W, H>;
+
+    fn board(&self) -> &Board<W, H> {
+        GameState::board(self)
+    }
+
+    fn to_move(&self) -> Player {
+        GameState::to_move(self)
+    }
+
+    fn result(&self) -> Option<TerminatedStatus> {
+        GameState::result(self)
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.is_terminated() {
+            return Vec::new();
+        }
+        drops(self.board())
+    }
+
+    fn apply_move(&mut self, player: Player, mv: Move) -> GamePlay {
+        match mv {
+            Move::Drop(col) => self.apply(player, col),
+            Move::Pop(_) if self.is_terminated() => GamePlay::InvalidBoard(IllegalMove::GameOver),
+            Move::Pop(_) if player != self.to_move() => {
+                GamePlay::InvalidBoard(IllegalMove::WrongPlayer)
+            }
+            Move::Pop(_) => GamePlay::InvalidBoard(IllegalMove::NotAllowed),
+        }
+    }
+
+    fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus> {
+        GameState::forfeit(self, player)
+    }
+}
+
+/// Drops in every column that is not full
+fn drops<const W: usize, const H: usize>(board: &Board<W, H>) -> Vec<Move> {
+    board
+        .valid_moves()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, valid)| *valid)
+        .map(|(col, _)| Move::Drop(col as Col))
+        .collect()
+}
+
+/// Pops of every column with a piece of `player` at the bottom
+fn pops<const W: usize, const H: usize>(board: &Board<W, H>, player: Player) -> Vec<Move> {
+    (0..W as Col)
+        .filter(|col| board.pieces(player) >> (*col as usize * H) & 1 == 1)
+        .map(Move::Pop)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::StandardGame;
+
+    use super::*;
+
+    #[test]
+    fn classic_moves() {
+        let mut state = StandardGame::default();
+        assert_eq!(state.legal_moves().len(), 7);
+        assert_eq!(
+            state.apply_move(Player::Red, Move::Pop(3)),
+            GamePlay::InvalidBoard(IllegalMove::NotAllowed)
+        );
+        assert_eq!(
+            state.apply_move(Player::Yellow, Move::Drop(3)),
+            GamePlay::InvalidBoard(IllegalMove::WrongPlayer)
+        );
+        for _ in 0..6 {
+            state.apply_move(state.to_move(), 0.into());
+        }
+        assert!(!state.legal_moves().contains(&Move::Drop(0)));
+        assert_eq!(state.legal_moves().len(), 6);
+    }
+
+    #[test]
+    fn classic_game_ends() {
+        let mut state = StandardGame::default();
+        for col in [0, 1, 0, 1, 0, 1] {
+            state.apply_move(state.to_move(), Move::Drop(col));
+        }
+        assert_eq!(
+            state.apply_move(Player::Red, Move::Drop(0)),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert!(state.legal_moves().is_empty());
+    }
+}
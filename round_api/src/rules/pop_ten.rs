@@ -0,0 +1,500 @@
+//! Pop Ten, where the board is filled first and players then score
+//! by popping their own pieces out of lines from the bottom row
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Col, GamePlay, IllegalMove, TerminatedStatus},
+    player::Player,
+    state::{check_players, InvalidState},
+    HEIGHT, MAX_PLAYERS, WIDTH, WIN_LENGTH,
+};
+
+use super::{drops, pops, Move, Ruleset};
+
+/// Stage of a game of Pop Ten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PopTenPhase {
+    /// Players fill the board one row after the other
+    Setup,
+    /// The player to move pops one of its pieces from the bottom row
+    Pop,
+    /// The player to move puts back the piece it just popped,
+    /// in another column whenever possible
+    Refill { popped: Col },
+}
+
+/// Pop Ten, played on a board filled by both players at the start.
+/// Players then pop their own pieces: a piece that was part of a line
+/// is captured and the player goes again, any other piece is dropped back.
+/// The first player to capture `target` pieces wins,
+/// the game is drawn if no piece is left to pop or when the same position
+/// comes back for the third time with a piece to pop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedPopTen<W, H>")]
+pub struct PopTen<const W: usize = WIDTH, const H: usize = HEIGHT> {
+    board: Board<W, H>,
+    to_move: Player,
+    phase: PopTenPhase,
+    ply: usize,
+    /// Pieces captured by each player, indexed by [`Player`]
+    captured: [usize; MAX_PLAYERS],
+    target: usize,
+    result: Option<TerminatedStatus>,
+    /// Every position reached with a piece to pop, with the player to move
+    positions: Vec<(u64, Player)>,
+}
+
+/// Fields of a [`PopTen`] game as read, before they are checked
+#[derive(Deserialize)]
+struct UncheckedPopTen<const W: usize, const H: usize> {
+    board: Board<W, H>,
+    to_move: Player,
+    phase: PopTenPhase,
+    ply: usize,
+    captured: [usize; MAX_PLAYERS],
+    target: usize,
+    result: Option<TerminatedStatus>,
+    positions: Vec<(u64, Player)>,
+}
+
+impl<const W: usize, const H: usize> TryFrom<UncheckedPopTen<W, H>> for PopTen<W, H> {
+    type Error = InvalidState;
+
+    fn try_from(game: UncheckedPopTen<W, H>) -> Result<Self, InvalidState> {
+        let UncheckedPopTen {
+            board,
+            to_move,
+            phase,
+            ply,
+            captured,
+            target,
+            result,
+            positions,
+        } = game;
+        check_players(&board, 2)?;
+        // Only red and yellow play Pop Ten
+        if let Some(outsider) = Player::ALL[2..]
+            .iter()
+            .find(|player| **player == to_move || captured[**player as usize] > 0)
+        {
+            return Err(InvalidState::Outsider(*outsider));
+        }
+        let game = Self {
+            board,
+            to_move,
+            phase,
+            ply,
+            captured,
+            target,
+            result: None,
+            positions,
+        };
+
+        let pieces = board.len();
+        let total = captured.iter().sum::<usize>();
+        let consistent = match phase {
+            PopTenPhase::Setup => {
+                let expected = Player::ALL[ply % 2];
+                if to_move != expected {
+                    return Err(InvalidState::ToMove {
+                        expected,
+                        found: to_move,
+                    });
+                }
+                // Rows are filled one after the other
+                let heights = board.heights();
+                let highest = heights.iter().max().copied().unwrap_or(0);
+                let lowest = heights.iter().min().copied().unwrap_or(0);
+                pieces == ply
+                    && pieces < W * H
+                    && total == 0
+                    && game.positions.is_empty()
+                    && highest - lowest <= 1
+            }
+            PopTenPhase::Pop => pieces + total == W * H,
+            PopTenPhase::Refill { popped } => (popped as usize) < W && pieces + total + 1 == W * H,
+        };
+        if !consistent {
+            return Err(InvalidState::Phase);
+        }
+
+        let stuck = pops(&board, to_move).is_empty();
+        let position = (board.key(), to_move);
+        let repeated = game
+            .positions
+            .iter()
+            .filter(|seen| **seen == position)
+            .count()
+            >= Self::REPETITIONS;
+        let ended = phase == PopTenPhase::Pop && (stuck || repeated);
+        if phase == PopTenPhase::Pop && result.is_none() && game.positions.last() != Some(&position)
+        {
+            return Err(InvalidState::Positions);
+        }
+        let winners = [Player::Red, Player::Yellow]
+            .into_iter()
+            .filter(|player| captured[*player as usize] >= target)
+            .collect::<Vec<_>>();
+        let consistent = match (winners.as_slice(), result) {
+            ([], None) => !ended,
+            ([], Some(TerminatedStatus::Draw)) => ended,
+            // Forfeits end the game without enough captures
+            ([], Some(TerminatedStatus::Win(_))) => true,
+            ([winner], Some(TerminatedStatus::Win(found))) => *winner == found,
+            _ => false,
+        };
+        if !consistent {
+            return Err(InvalidState::Result(result));
+        }
+        Ok(Self { result, ..game })
+    }
+}
+
+impl<const W: usize, const H: usize> Default for PopTen<W, H> {
+    fn default() -> Self {
+        Self::with_target(10)
+    }
+}
+
+impl<const W: usize, const H: usize> PopTen<W, H> {
+    const REPETITIONS: usize = 3;
+
+    /// New game won by the first player capturing `target` pieces
+    pub fn with_target(target: usize) -> Self {
+        Self {
+            board: Board::with_win_length(WIN_LENGTH),
+            to_move: Player::Red,
+            phase: PopTenPhase::Setup,
+            ply: 0,
+            captured: [0; MAX_PLAYERS],
+            target,
+            result: None,
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn phase(&self) -> PopTenPhase {
+        self.phase
+    }
+
+    /// Number of moves played so far
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Number of pieces captured by `player`
+    pub fn captured(&self, player: Player) -> usize {
+        self.captured[player as usize]
+    }
+
+    /// Error explaining why `mv` is not a legal move
+    fn refusal(&self, player: Player, mv: Move) -> IllegalMove {
+        let mut board = self.board;
+        match mv {
+            Move::Drop(col) => board.play(player, col).err(),
+            Move::Pop(col) => board.pop(player, col).err(),
+        }
+        .unwrap_or(IllegalMove::NotAllowed)
+    }
+}
+
+impl<const W: usize, const H: usize> Ruleset for PopTen<W, H> {
+    type Board = Board<W, H>;
+
+    fn board(&self) -> &Board<W, H> {
+        &self.board
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn result(&self) -> Option<TerminatedStatus> {
+        self.result
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.is_terminated() {
+            return Vec::new();
+        }
+        match self.phase {
+            PopTenPhase::Setup => {
+                let heights = self.board.heights();
+                let lowest = heights.iter().min().copied().unwrap_or(0);
+                drops(&self.board)
+                    .into_iter()
+                    .filter(|mv| heights[mv.col() as usize] == lowest)
+                    .collect()
+            }
+            PopTenPhase::Pop => pops(&self.board, self.to_move),
+            PopTenPhase::Refill { popped } => {
+                let moves = drops(&self.board);
+                if moves.iter().all(|mv| mv.col() == popped) {
+                    moves
+                } else {
+                    moves.into_iter().filter(|mv| mv.col() != popped).collect()
+                }
+            }
+        }
+    }
+
+    fn apply_move(&mut self, player: Player, mv: Move) -> GamePlay {
+        if self.is_terminated() {
+            return GamePlay::InvalidBoard(IllegalMove::GameOver);
+        }
+        if player != self.to_move {
+            return GamePlay::InvalidBoard(IllegalMove::WrongPlayer);
+        }
+        if !self.legal_moves().contains(&mv) {
+            return GamePlay::InvalidBoard(self.refusal(player, mv));
+        }
+
+        self.ply += 1;
+        match (self.phase, mv) {
+            (PopTenPhase::Setup, Move::Drop(col)) => {
+                self.board.play(player, col).expect("Drop was checked");
+                self.to_move = player.opponent();
+                if self.board.is_full() {
+                    self.phase = PopTenPhase::Pop;
+                }
+            }
+            (PopTenPhase::Pop, Move::Pop(col)) => {
+                let in_line = !self.board.winning_lines(0, col, player).is_empty();
+                self.board.pop(player, col).expect("Pop was checked");
+                if in_line {
+                    self.captured[player as usize] += 1;
+                    if self.captured[player as usize] >= self.target {
+                        self.result = Some(TerminatedStatus::Win(player));
+                    }
+                } else {
+                    self.phase = PopTenPhase::Refill { popped: col };
+                }
+            }
+            (PopTenPhase::Refill { .. }, Move::Drop(col)) => {
+                self.board.play(player, col).expect("Drop was checked");
+                self.phase = PopTenPhase::Pop;
+                self.to_move = player.opponent();
+            }
+            _ => unreachable!("Legal moves match the phase"),
+        }
+
+        // A player without any piece at the bottom has to pass
+        if self.result.is_none() && self.phase == PopTenPhase::Pop {
+            if pops(&self.board, self.to_move).is_empty() {
+                self.to_move = self.to_move.opponent();
+            }
+            if pops(&self.board, self.to_move).is_empty() {
+                self.result = Some(TerminatedStatus::Draw);
+            } else {
+                let position = (self.board.key(), self.to_move);
+                self.positions.push(position);
+                let repetitions = self
+                    .positions
+                    .iter()
+                    .filter(|seen| **seen == position)
+                    .count();
+                if repetitions >= Self::REPETITIONS {
+                    self.result = Some(TerminatedStatus::Draw);
+                }
+            }
+        }
+        match self.result {
+            Some(status) => GamePlay::GameTerminated(status),
+            None => GamePlay::ValidPlay,
+        }
+    }
+
+    fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus> {
+        Some(
+            *self
+                .result
+                .get_or_insert(TerminatedStatus::Win(player.opponent())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use super::*;
+
+    type StandardPopTen = PopTen<7, 6>;
+
+    /// Fills every row dropping in the columns in the same order.
+    /// Rows go `R R Y Y R R Y` and `Y Y R R Y Y R`, without any line
+    const STRIPES: [Col; 7] = [0, 2, 1, 3, 4, 6, 5];
+
+    fn set_up(game: &mut StandardPopTen, order: [Col; 7]) {
+        while game.phase() == PopTenPhase::Setup {
+            for col in order {
+                assert_eq!(
+                    game.apply_move(game.to_move(), Move::Drop(col)),
+                    GamePlay::ValidPlay
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn setup_fills_rows() {
+        let mut game = StandardPopTen::default();
+        assert_eq!(game.legal_moves().len(), 7);
+        game.apply_move(Player::Red, Move::Drop(2));
+        assert!(!game.legal_moves().contains(&Move::Drop(2)));
+        assert_eq!(
+            game.apply_move(Player::Yellow, Move::Drop(2)),
+            GamePlay::InvalidBoard(IllegalMove::NotAllowed)
+        );
+        assert_eq!(
+            game.apply_move(Player::Yellow, Move::Pop(2)),
+            GamePlay::InvalidBoard(IllegalMove::CannotPop)
+        );
+
+        let mut game = StandardPopTen::default();
+        set_up(&mut game, STRIPES);
+        assert_eq!(game.phase(), PopTenPhase::Pop);
+        assert_eq!(game.ply(), 42);
+        assert_eq!(game.to_move(), Player::Red);
+        assert_eq!(
+            game.legal_moves(),
+            vec![Move::Pop(0), Move::Pop(1), Move::Pop(4), Move::Pop(5)]
+        );
+    }
+
+    #[test]
+    fn popped_pieces_go_back() {
+        let mut game = StandardPopTen::default();
+        set_up(&mut game, STRIPES);
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(2)),
+            GamePlay::InvalidBoard(IllegalMove::CannotPop)
+        );
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(0)),
+            GamePlay::ValidPlay
+        );
+        assert_eq!(game.phase(), PopTenPhase::Refill { popped: 0 });
+        assert_eq!(game.to_move(), Player::Red);
+        // The popped column is the only one with room left
+        assert_eq!(game.legal_moves(), vec![Move::Drop(0)]);
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Drop(0)),
+            GamePlay::ValidPlay
+        );
+        assert_eq!(game.phase(), PopTenPhase::Pop);
+        assert_eq!(game.to_move(), Player::Yellow);
+        assert_eq!(game.captured(Player::Red), 0);
+        assert_eq!(game.captured(Player::Green), 0);
+    }
+
+    #[test]
+    fn pieces_in_line_are_captured() {
+        // Red lines up the first four columns of the bottom row
+        let mut game = StandardPopTen::with_target(2);
+        set_up(&mut game, [0, 4, 1, 5, 2, 6, 3]);
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(0)),
+            GamePlay::ValidPlay
+        );
+        assert_eq!(game.captured(Player::Red), 1);
+        assert_eq!(game.phase(), PopTenPhase::Pop);
+        assert_eq!(game.to_move(), Player::Red);
+        assert!(game.board().valid_moves()[0]);
+
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(1)),
+            GamePlay::ValidPlay
+        );
+        assert_eq!(game.phase(), PopTenPhase::Refill { popped: 1 });
+        let mv = game.legal_moves()[0];
+        assert_ne!(mv, Move::Drop(1));
+        game.apply_move(Player::Red, mv);
+        assert_eq!(game.to_move(), Player::Yellow);
+    }
+
+    #[test]
+    fn capturing_the_target_wins() {
+        let mut game = StandardPopTen::with_target(1);
+        set_up(&mut game, [0, 4, 1, 5, 2, 6, 3]);
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(3)),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn repetitions_are_drawn() {
+        let mut game = StandardPopTen::default();
+        set_up(&mut game, STRIPES);
+        // Both players pop the bottom of the first column and put the piece back on top,
+        // which brings the column back every two turns
+        let cycle = [Move::Pop(0), Move::Drop(0), Move::Pop(0), Move::Drop(0)];
+        for mv in cycle {
+            assert_eq!(game.apply_move(game.to_move(), mv), GamePlay::ValidPlay);
+        }
+        for mv in &cycle[..3] {
+            assert_eq!(game.apply_move(game.to_move(), *mv), GamePlay::ValidPlay);
+        }
+        assert_eq!(
+            game.apply_move(Player::Yellow, Move::Drop(0)),
+            GamePlay::GameTerminated(TerminatedStatus::Draw)
+        );
+        assert_eq!(
+            game.captured(Player::Red) + game.captured(Player::Yellow),
+            0
+        );
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn forfeits_end_the_game() {
+        let mut game = StandardPopTen::default();
+        assert_eq!(
+            game.forfeit(Player::Red),
+            Some(TerminatedStatus::Win(Player::Yellow))
+        );
+        assert_eq!(game.result(), Some(TerminatedStatus::Win(Player::Yellow)));
+    }
+
+    #[test]
+    fn random_games_end() {
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..20 {
+            let mut game = StandardPopTen::default();
+            while let Some(mv) = game.legal_moves().choose(&mut rng).copied() {
+                game.apply_move(game.to_move(), mv);
+                assert!(game.ply() < 10_000, "The game does not end");
+            }
+            assert!(game.is_terminated());
+        }
+    }
+
+    #[test]
+    fn deserialized_games_are_checked() {
+        let mut game = StandardPopTen::with_target(2);
+        set_up(&mut game, [0, 4, 1, 5, 2, 6, 3]);
+        game.apply_move(Player::Red, Move::Pop(0));
+        let json = serde_json::to_value(&game).unwrap();
+        assert_eq!(
+            serde_json::from_value::<StandardPopTen>(json.clone()).unwrap(),
+            game
+        );
+
+        let rejection = |field: &str, value: serde_json::Value| {
+            let mut json = json.clone();
+            json[field] = value;
+            serde_json::from_value::<StandardPopTen>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(rejection("phase", "Setup".into()).contains("should be to move"));
+        assert!(rejection("captured", serde_json::json!([2, 0, 0, 0])).contains("stage"));
+        assert!(rejection("captured", serde_json::json!([0, 0, 1, 0])).contains("Green"));
+        assert!(rejection("to_move", "Blue".into()).contains("Blue"));
+        assert!(rejection("target", 1.into()).contains("does not match the board"));
+        assert!(rejection("positions", serde_json::json!([])).contains("positions seen"));
+    }
+}
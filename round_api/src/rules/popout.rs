@@ -0,0 +1,292 @@
+//! PopOut, Connect 4 where players can also take back one of their own pieces
+//! from the bottom of a column, shifting the column down
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, GamePlay, IllegalMove, TerminatedStatus},
+    player::Player,
+    state::{check_players, InvalidState},
+    HEIGHT, WIDTH, WIN_LENGTH,
+};
+
+use super::{drops, pops, Move, Ruleset};
+
+/// PopOut, where a player can also remove one of its own pieces
+/// from the bottom of a column instead of dropping one.
+/// A pop lining up pieces for both players wins for the player popping.
+/// Pops keep a full board going, the game is drawn when the same position
+/// comes back for the third time or the player to move is stuck
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedPopOut<W, H>")]
+pub struct PopOut<const W: usize = WIDTH, const H: usize = HEIGHT> {
+    board: Board<W, H>,
+    to_move: Player,
+    ply: usize,
+    result: Option<TerminatedStatus>,
+    /// Every position reached so far, with the player to move
+    positions: Vec<(u64, Player)>,
+}
+
+/// Fields of a [`PopOut`] game as read, before they are checked
+#[derive(Deserialize)]
+struct UncheckedPopOut<const W: usize, const H: usize> {
+    board: Board<W, H>,
+    to_move: Player,
+    ply: usize,
+    result: Option<TerminatedStatus>,
+    positions: Vec<(u64, Player)>,
+}
+
+impl<const W: usize, const H: usize> TryFrom<UncheckedPopOut<W, H>> for PopOut<W, H> {
+    type Error = InvalidState;
+
+    fn try_from(game: UncheckedPopOut<W, H>) -> Result<Self, InvalidState> {
+        let UncheckedPopOut {
+            board,
+            to_move,
+            ply,
+            result,
+            positions,
+        } = game;
+        check_players(&board, 2)?;
+        let expected = Player::ALL[ply % 2];
+        if to_move != expected {
+            return Err(InvalidState::ToMove {
+                expected,
+                found: to_move,
+            });
+        }
+        if positions.len() != ply + 1 || positions.last() != Some(&(board.key(), to_move)) {
+            return Err(InvalidState::Positions);
+        }
+
+        let game = Self {
+            board,
+            to_move,
+            ply,
+            result: None,
+            positions,
+        };
+        let expected = game.outcome(to_move.opponent());
+        let consistent = match (expected, result) {
+            // Forfeits end the game without a winning line
+            (None, Some(TerminatedStatus::Win(_))) => true,
+            (expected, result) => expected == result,
+        };
+        if !consistent {
+            return Err(InvalidState::Result(result));
+        }
+        Ok(Self { result, ..game })
+    }
+}
+
+impl<const W: usize, const H: usize> Default for PopOut<W, H> {
+    fn default() -> Self {
+        Self::with_win_length(WIN_LENGTH)
+    }
+}
+
+impl<const W: usize, const H: usize> PopOut<W, H> {
+    const REPETITIONS: usize = 3;
+
+    /// New game where `win_length` pieces in a row are needed to win
+    pub fn with_win_length(win_length: usize) -> Self {
+        let board = Board::with_win_length(win_length);
+        Self {
+            board,
+            to_move: Player::Red,
+            ply: 0,
+            result: None,
+            positions: vec![(board.key(), Player::Red)],
+        }
+    }
+
+    /// Number of moves played so far
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Outcome once `player` has moved, looking at the whole board as a pop
+    /// can complete lines anywhere in its column
+    fn outcome(&self, player: Player) -> Option<TerminatedStatus> {
+        let opponent = player.opponent();
+        if self.board.has_won(player) {
+            return Some(TerminatedStatus::Win(player));
+        }
+        if self.board.has_won(opponent) {
+            return Some(TerminatedStatus::Win(opponent));
+        }
+        let position = (self.board.key(), opponent);
+        let repetitions = self
+            .positions
+            .iter()
+            .filter(|seen| **seen == position)
+            .count();
+        if repetitions >= Self::REPETITIONS || self.legal_moves().is_empty() {
+            return Some(TerminatedStatus::Draw);
+        }
+        None
+    }
+}
+
+impl<const W: usize, const H: usize> Ruleset for PopOut<W, H> {
+    type Board = Board<W, H>;
+
+    fn board(&self) -> &Board<W, H> {
+        &self.board
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn result(&self) -> Option<TerminatedStatus> {
+        self.result
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.is_terminated() {
+            return Vec::new();
+        }
+        let mut moves = drops(&self.board);
+        moves.extend(pops(&self.board, self.to_move));
+        moves
+    }
+
+    fn apply_move(&mut self, player: Player, mv: Move) -> GamePlay {
+        if self.is_terminated() {
+            return GamePlay::InvalidBoard(IllegalMove::GameOver);
+        }
+        if player != self.to_move {
+            return GamePlay::InvalidBoard(IllegalMove::WrongPlayer);
+        }
+        let played = match mv {
+            Move::Drop(col) => self.board.play(player, col).map(|_| ()),
+            Move::Pop(col) => self.board.pop(player, col),
+        };
+        if let Err(err) = played {
+            return GamePlay::InvalidBoard(err);
+        }
+
+        self.ply += 1;
+        self.to_move = player.opponent();
+        self.positions.push((self.board.key(), self.to_move));
+        self.result = self.outcome(player);
+        match self.result {
+            Some(status) => GamePlay::GameTerminated(status),
+            None => GamePlay::ValidPlay,
+        }
+    }
+
+    fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus> {
+        Some(
+            *self
+                .result
+                .get_or_insert(TerminatedStatus::Win(player.opponent())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type StandardPopOut = PopOut<7, 6>;
+
+    fn play_all(game: &mut StandardPopOut, moves: &[Move]) -> GamePlay {
+        moves.iter().fold(GamePlay::ValidPlay, |_, mv| {
+            game.apply_move(game.to_move(), *mv)
+        })
+    }
+
+    #[test]
+    fn pops_are_legal_moves() {
+        let mut game = StandardPopOut::default();
+        assert_eq!(game.legal_moves().len(), 7);
+        play_all(&mut game, &[Move::Drop(3), Move::Drop(4)]);
+        assert!(game.legal_moves().contains(&Move::Pop(3)));
+        assert!(!game.legal_moves().contains(&Move::Pop(4)));
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(4)),
+            GamePlay::InvalidBoard(IllegalMove::CannotPop)
+        );
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(3)),
+            GamePlay::ValidPlay
+        );
+        assert_eq!(game.board().pieces(Player::Red), 0);
+        assert_eq!(game.ply(), 3);
+    }
+
+    #[test]
+    fn popping_can_win() {
+        // Red lines up the second row by popping its bottom piece in column 4
+        let mut game = StandardPopOut::default();
+        let moves = [2, 1, 1, 3, 3, 5, 2, 6, 4, 4, 4, 6].map(Move::Drop);
+        assert_eq!(play_all(&mut game, &moves), GamePlay::ValidPlay);
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(4)),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+    }
+
+    #[test]
+    fn popping_for_both_wins() {
+        let mut game = StandardPopOut::default();
+        // The pop of column 0 lines up red on the second row and yellow on the first
+        let moves = [0, 1, 1, 2, 2, 3, 3, 0, 0, 6];
+        assert_eq!(
+            play_all(&mut game, &moves.map(Move::Drop)),
+            GamePlay::ValidPlay
+        );
+        assert!(!game.board().has_won(Player::Red));
+        assert!(!game.board().has_won(Player::Yellow));
+        assert_eq!(
+            game.apply_move(Player::Red, Move::Pop(0)),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert!(game.board().has_won(Player::Yellow));
+    }
+
+    #[test]
+    fn repetitions_are_drawn() {
+        let mut game = StandardPopOut::default();
+        let cycle = [Move::Drop(0), Move::Drop(6), Move::Pop(0), Move::Pop(6)];
+        assert_eq!(play_all(&mut game, &cycle), GamePlay::ValidPlay);
+        assert_eq!(
+            play_all(&mut game, &cycle),
+            GamePlay::GameTerminated(TerminatedStatus::Draw)
+        );
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn deserialized_games_are_checked() {
+        let mut game = StandardPopOut::default();
+        play_all(&mut game, &[Move::Drop(3), Move::Drop(3), Move::Pop(3)]);
+        let json = serde_json::to_value(&game).unwrap();
+        assert_eq!(
+            serde_json::from_value::<StandardPopOut>(json.clone()).unwrap(),
+            game
+        );
+
+        let rejection = |field: &str, value: serde_json::Value| {
+            let mut json = json.clone();
+            json[field] = value;
+            serde_json::from_value::<StandardPopOut>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(rejection("ply", 2.into()).contains("should be to move"));
+        assert!(rejection("to_move", "Red".into()).contains("should be to move"));
+        assert!(rejection("positions", serde_json::json!([])).contains("positions seen"));
+        assert!(rejection("result", "Draw".into()).contains("does not match the board"));
+        let mut green = json.clone();
+        green["board"]["columns"][0] = "g".into();
+        assert!(serde_json::from_value::<StandardPopOut>(green)
+            .unwrap_err()
+            .to_string()
+            .contains("Green does not take part"));
+    }
+}
@@ -0,0 +1,746 @@
+//! Perfect play solver, used as ground truth to grade agents.
+//! Positions are solved with a negamax search using alpha-beta pruning,
+//! a transposition table and center-first move ordering,
+//! following the approach of Pascal Pons' Connect 4 solver.
+//!
+//! Internally a score is given to every position for the player to move:
+//! zero for a draw, positive for a win and negative for a loss,
+//! the earlier the end of the game the larger the absolute value
+
+use std::cmp::Ordering;
+
+use crate::{
+    board::{Board, Col},
+    player::Player,
+};
+
+/// Result of a position for the player to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// Exact value of a position for the player to move, under perfect play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Evaluation {
+    pub outcome: Outcome,
+    /// Moves left until the end of the game, counting the moves of both players
+    pub plies: usize,
+}
+
+impl Evaluation {
+    fn from_score(score: i32, moves: usize, cells: usize) -> Self {
+        // Moves played before the winning one, which is played by the player to move on a win
+        let winning_ply = |score: i32, player_moves: usize| {
+            let ply = cells + 1 - 2 * score as usize;
+            if (ply + player_moves).is_multiple_of(2) {
+                ply
+            } else {
+                ply - 1
+            }
+        };
+        match score.cmp(&0) {
+            Ordering::Equal => Self {
+                outcome: Outcome::Draw,
+                plies: cells - moves,
+            },
+            Ordering::Greater => Self {
+                outcome: Outcome::Win,
+                plies: winning_ply(score, moves) + 1 - moves,
+            },
+            Ordering::Less => Self {
+                outcome: Outcome::Loss,
+                plies: winning_ply(-score, moves + 1) + 1 - moves,
+            },
+        }
+    }
+
+    /// Value of the position one move earlier, seen by the other player
+    fn parent(self) -> Self {
+        Self {
+            outcome: match self.outcome {
+                Outcome::Win => Outcome::Loss,
+                Outcome::Draw => Outcome::Draw,
+                Outcome::Loss => Outcome::Win,
+            },
+            plies: self.plies + 1,
+        }
+    }
+
+    /// Sort key, faster wins and slower losses being better
+    fn rank(&self) -> (u8, isize) {
+        match self.outcome {
+            Outcome::Win => (2, -(self.plies as isize)),
+            Outcome::Draw => (1, self.plies as isize),
+            Outcome::Loss => (0, self.plies as isize),
+        }
+    }
+}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Evaluations are ordered from the point of view of the player to move,
+/// the greatest one being the best
+impl Ord for Evaluation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+/// Value of a position and of every move playable from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analysis<const W: usize> {
+    pub evaluation: Evaluation,
+    /// Best column to play, the most central one on ties.
+    /// `None` once the game is over
+    pub best_move: Option<Col>,
+    /// Value for the player to move of playing each column,
+    /// `None` for full columns or once the game is over
+    pub scores: [Option<Evaluation>; W],
+}
+
+#[inline]
+const fn shift_left(cells: u64, n: usize) -> u64 {
+    if n >= u64::BITS as usize {
+        0
+    } else {
+        cells << n
+    }
+}
+
+#[inline]
+const fn shift_right(cells: u64, n: usize) -> u64 {
+    if n >= u64::BITS as usize {
+        0
+    } else {
+        cells >> n
+    }
+}
+
+/// Columns from the center outwards
+fn center_order<const W: usize>() -> [Col; W] {
+    std::array::from_fn(|i| {
+        let offset = (1 - 2 * (i as isize % 2)) * (i as isize + 1) / 2;
+        (W as isize / 2 + offset) as Col
+    })
+}
+
+/// Position as seen by the solver, with an empty row above every column.
+/// The spare row stops lines from wrapping between columns
+/// and gives every position a unique key
+#[derive(Debug, Clone, Copy)]
+struct Position<const W: usize, const H: usize> {
+    /// Cells taken by the player to move
+    current: u64,
+    mask: u64,
+    moves: usize,
+    win_length: usize,
+}
+
+impl<const W: usize, const H: usize> Position<W, H> {
+    const FITS: () = assert!(
+        W * (H + 1) <= u64::BITS as usize,
+        "The solver needs a spare row above the board"
+    );
+    const BOTTOM: u64 = {
+        let mut bottom = 0;
+        let mut col = 0;
+        while col < W {
+            bottom |= 1 << (col * (H + 1));
+            col += 1;
+        }
+        bottom
+    };
+    const CELLS: u64 = Self::BOTTOM * ((1 << H) - 1);
+    /// Shifts moving a cell one step vertically, horizontally and along both diagonals
+    const SHIFTS: [usize; 4] = [1, H + 1, H + 2, H];
+
+    fn from_board(board: &Board<W, H>) -> Self {
+        let () = Self::FITS;
+        let spread = |pieces: u64| {
+            (0..W).fold(0, |acc, col| {
+                acc | (pieces >> (col * H) & ((1 << H) - 1)) << (col * (H + 1))
+            })
+        };
+        let red = spread(board.pieces(Player::Red));
+        let yellow = spread(board.pieces(Player::Yellow));
+        let (red_count, yellow_count) = (red.count_ones(), yellow.count_ones());
+        Self {
+            current: if red_count > yellow_count {
+                yellow
+            } else {
+                red
+            },
+            mask: red | yellow,
+            moves: (red_count + yellow_count) as usize,
+            win_length: board.win_length(),
+        }
+    }
+
+    #[inline]
+    fn column(col: usize) -> u64 {
+        ((1 << H) - 1) << (col * (H + 1))
+    }
+
+    /// Cell where a piece lands in every column that is not full
+    #[inline]
+    fn possible(&self) -> u64 {
+        (self.mask + Self::BOTTOM) & Self::CELLS
+    }
+
+    /// Plays the cell `mv` for the player to move, handing the turn over
+    #[inline]
+    fn play(&mut self, mv: u64) {
+        self.current ^= self.mask;
+        self.mask |= mv;
+        self.moves += 1;
+    }
+
+    /// Unique key of the position, shared with its mirror image
+    #[inline]
+    fn key(&self) -> u64 {
+        let key = self.current + self.mask;
+        let column = (1 << (H + 1)) - 1;
+        let mirror = (0..W).fold(0, |acc, col| {
+            acc | (key >> (col * (H + 1)) & column) << ((W - 1 - col) * (H + 1))
+        });
+        key.min(mirror)
+    }
+
+    /// Empty cells completing a line of `stones`
+    #[inline]
+    fn winning_cells(&self, stones: u64, mask: u64) -> u64 {
+        let winning = if self.win_length == 4 {
+            Self::completing_fours(stones)
+        } else {
+            Self::completing_lines(stones, self.win_length)
+        };
+        winning & Self::CELLS & !mask
+    }
+
+    /// Cells completing a line of `length` stones, taken or not
+    fn completing_lines(stones: u64, length: usize) -> u64 {
+        let mut winning = 0;
+        let mut ahead = [u64::MAX; u64::BITS as usize];
+        for shift in Self::SHIFTS {
+            // ahead[i]: cells followed by `i` stones along the direction
+            for i in 1..length {
+                ahead[i] = ahead[i - 1] & shift_right(stones, i * shift);
+            }
+            // Cells preceded by `i` stones and followed by the rest of the line
+            let mut behind = u64::MAX;
+            for i in 0..length {
+                if i > 0 {
+                    behind &= shift_left(stones, i * shift);
+                }
+                winning |= behind & ahead[length - 1 - i];
+            }
+        }
+        winning
+    }
+
+    /// Same as [`Self::completing_lines`] for the usual four in a row,
+    /// with every shift known at compile time
+    #[inline]
+    fn completing_fours(stones: u64) -> u64 {
+        let mut winning = 0;
+        for shift in Self::SHIFTS {
+            let behind = shift_left(stones, shift) & shift_left(stones, 2 * shift);
+            winning |= behind & shift_left(stones, 3 * shift);
+            winning |= behind & shift_right(stones, shift);
+            let ahead = shift_right(stones, shift) & shift_right(stones, 2 * shift);
+            winning |= ahead & shift_left(stones, shift);
+            winning |= ahead & shift_right(stones, 3 * shift);
+        }
+        winning
+    }
+
+    #[inline]
+    fn can_win_next(&self) -> bool {
+        self.winning_cells(self.current, self.mask) & self.possible() != 0
+    }
+
+    /// Moves that do not let the opponent win on the next move
+    fn non_losing_moves(&self) -> u64 {
+        let mut possible = self.possible();
+        let opponent_wins = self.winning_cells(self.current ^ self.mask, self.mask);
+        let forced = possible & opponent_wins;
+        if forced != 0 {
+            if forced & (forced - 1) != 0 {
+                // Two threats can not be blocked at once
+                return 0;
+            }
+            possible = forced;
+        }
+        // Never play right below a winning cell of the opponent
+        possible & !(opponent_wins >> 1)
+    }
+
+    /// Number of winning cells the player to move has after playing `mv`
+    #[inline]
+    fn move_score(&self, mv: u64) -> u32 {
+        self.winning_cells(self.current | mv, self.mask | mv)
+            .count_ones()
+    }
+}
+
+/// Fixed size table remembering bounds of searched positions.
+/// A key is split between the index of its entry and the quotient stored in it,
+/// next to the value. Entries are overwritten on collision
+#[derive(Debug, Clone)]
+struct TranspositionTable {
+    entries: Vec<u64>,
+}
+
+impl TranspositionTable {
+    fn new(bits: u32) -> Self {
+        assert!(
+            (8..=40).contains(&bits),
+            "Table must have between 2^8 and 2^40 entries"
+        );
+        // An odd size spreads the keys, which share a lot of low bits
+        Self {
+            entries: vec![0; (1 << bits) + 1],
+        }
+    }
+
+    /// Stored value of the position, 0 when missing
+    #[inline]
+    fn get(&self, key: u64) -> u8 {
+        let size = self.entries.len() as u64;
+        let entry = self.entries[(key % size) as usize];
+        if entry >> 8 == key / size {
+            entry as u8
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn put(&mut self, key: u64, value: u8) {
+        let size = self.entries.len() as u64;
+        self.entries[(key % size) as usize] = (key / size) << 8 | value as u64;
+    }
+
+    fn clear(&mut self) {
+        self.entries.fill(0);
+    }
+}
+
+/// Solver for boards of `W` columns and `H` rows.
+/// The board needs a spare row in the bitboard, `W * (H + 1)` must not exceed 64,
+/// which leaves out the default 8x8 board.
+/// Searched positions are kept between calls, until the win length changes
+#[derive(Debug, Clone)]
+pub struct Solver<const W: usize, const H: usize> {
+    table: TranspositionTable,
+    win_length: usize,
+    nodes: u64,
+}
+
+impl<const W: usize, const H: usize> Default for Solver<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Solver<W, H> {
+    const CELLS: usize = W * H;
+    const MIN_SCORE: i32 = -(Self::CELLS as i32) / 2;
+    const MAX_SCORE: i32 = (Self::CELLS as i32 + 1) / 2;
+
+    /// Solver with a transposition table of 2^23 entries, about 64MB
+    pub fn new() -> Self {
+        Self::with_table_bits(23)
+    }
+
+    /// Solver with a transposition table of `2^bits` entries, `bits` going from 8 to 40
+    pub fn with_table_bits(bits: u32) -> Self {
+        let () = Position::<W, H>::FITS;
+        Self {
+            table: TranspositionTable::new(bits),
+            win_length: 0,
+            nodes: 0,
+        }
+    }
+
+    /// Number of positions searched since the solver was created
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Forgets every searched position
+    pub fn reset(&mut self) {
+        self.table.clear();
+    }
+
+    /// Exact value of the board for the player to move.
+    /// Red is expected to move first, so the player to move is yellow
+    /// when red has one more piece on the board.
+    /// A board that is already won is lost for the player to move
+    pub fn solve(&mut self, board: &Board<W, H>) -> Evaluation {
+        match self.prepare(board) {
+            Some(position) => self.evaluate(position),
+            None => Evaluation {
+                outcome: Outcome::Loss,
+                plies: 0,
+            },
+        }
+    }
+
+    /// Value of the board and of every column, for the player to move
+    pub fn analyze(&mut self, board: &Board<W, H>) -> Analysis<W> {
+        let Some(position) = self.prepare(board) else {
+            return Analysis {
+                evaluation: self.solve(board),
+                best_move: None,
+                scores: [None; W],
+            };
+        };
+        let winning = position.winning_cells(position.current, position.mask);
+        let scores = std::array::from_fn(|col| {
+            let mv = position.possible() & Position::<W, H>::column(col);
+            if mv == 0 {
+                None
+            } else if mv & winning != 0 {
+                Some(Evaluation {
+                    outcome: Outcome::Win,
+                    plies: 1,
+                })
+            } else {
+                let mut child = position;
+                child.play(mv);
+                Some(self.evaluate(child).parent())
+            }
+        });
+        let best_move = center_order::<W>()
+            .into_iter()
+            .filter_map(|col| scores[col as usize].map(|score| (col, score)))
+            // The first maximum is the most central one
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+            .map(|(col, _)| col);
+        Analysis {
+            evaluation: self.evaluate(position),
+            best_move,
+            scores,
+        }
+    }
+
+    /// Best column to play, `None` once the game is over
+    pub fn best_move(&mut self, board: &Board<W, H>) -> Option<Col> {
+        self.analyze(board).best_move
+    }
+
+    /// Position to search, `None` if the game is already won
+    fn prepare(&mut self, board: &Board<W, H>) -> Option<Position<W, H>> {
+        if board.win_length() != self.win_length {
+            self.reset();
+            self.win_length = board.win_length();
+        }
+        if board.has_won(Player::Red) || board.has_won(Player::Yellow) {
+            return None;
+        }
+        Some(Position::from_board(board))
+    }
+
+    fn evaluate(&mut self, position: Position<W, H>) -> Evaluation {
+        Evaluation::from_score(self.score(position), position.moves, Self::CELLS)
+    }
+
+    /// Exact score, narrowing the range with null window searches
+    fn score(&mut self, position: Position<W, H>) -> i32 {
+        let moves = position.moves as i32;
+        let cells = Self::CELLS as i32;
+        if position.can_win_next() {
+            return (cells + 1 - moves) / 2;
+        }
+        let mut min = -(cells - moves) / 2;
+        let mut max = (cells + 1 - moves) / 2;
+        while min < max {
+            // Looking for draws and quick results first pays off
+            let mut med = min + (max - min) / 2;
+            if med <= 0 && min / 2 < med {
+                med = min / 2;
+            } else if med >= 0 && max / 2 > med {
+                med = max / 2;
+            }
+            let score = self.negamax(position, med, med + 1);
+            if score <= med {
+                max = score;
+            } else {
+                min = score;
+            }
+        }
+        min
+    }
+
+    /// Score of a position where the player to move can not win immediately,
+    /// exact when it lies within `alpha..beta`, otherwise only a bound on the same side
+    fn negamax(&mut self, position: Position<W, H>, mut alpha: i32, mut beta: i32) -> i32 {
+        debug_assert!(alpha < beta);
+        debug_assert!(!position.can_win_next());
+        self.nodes += 1;
+
+        let moves = position.moves as i32;
+        let cells = Self::CELLS as i32;
+        let next = position.non_losing_moves();
+        if next == 0 {
+            return -(cells - moves) / 2;
+        }
+        if moves >= cells - 2 {
+            return 0;
+        }
+
+        // The opponent can not win on the next move
+        let min = -(cells - 2 - moves) / 2;
+        if alpha < min {
+            alpha = min;
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+        // Nor can the player to move
+        let max = (cells - 1 - moves) / 2;
+        if beta > max {
+            beta = max;
+            if alpha >= beta {
+                return beta;
+            }
+        }
+
+        let key = position.key();
+        let value = self.table.get(key) as i32;
+        if value > Self::MAX_SCORE - Self::MIN_SCORE + 1 {
+            let min = value + 2 * Self::MIN_SCORE - Self::MAX_SCORE - 2;
+            if alpha < min {
+                alpha = min;
+                if alpha >= beta {
+                    return alpha;
+                }
+            }
+        } else if value != 0 {
+            let max = value + Self::MIN_SCORE - 1;
+            if beta > max {
+                beta = max;
+                if alpha >= beta {
+                    return beta;
+                }
+            }
+        }
+
+        // Center first, then the moves creating the most threats
+        let mut candidates = [(0, 0); W];
+        let mut count = 0;
+        for col in center_order::<W>() {
+            let mv = next & Position::<W, H>::column(col as usize);
+            if mv != 0 {
+                let score = position.move_score(mv);
+                let mut index = count;
+                while index > 0 && candidates[index - 1].1 < score {
+                    candidates[index] = candidates[index - 1];
+                    index -= 1;
+                }
+                candidates[index] = (mv, score);
+                count += 1;
+            }
+        }
+
+        for &(mv, _) in &candidates[..count] {
+            let mut child = position;
+            child.play(mv);
+            let score = -self.negamax(child, -beta, -alpha);
+            if score >= beta {
+                self.table.put(
+                    key,
+                    (score + Self::MAX_SCORE - 2 * Self::MIN_SCORE + 2) as u8,
+                );
+                return score;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+        }
+        self.table.put(key, (alpha - Self::MIN_SCORE + 1) as u8);
+        alpha
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use super::*;
+    use crate::board::StandardBoard;
+
+    const fn win(plies: usize) -> Evaluation {
+        Evaluation {
+            outcome: Outcome::Win,
+            plies,
+        }
+    }
+
+    const fn loss(plies: usize) -> Evaluation {
+        Evaluation {
+            outcome: Outcome::Loss,
+            plies,
+        }
+    }
+
+    const fn draw(plies: usize) -> Evaluation {
+        Evaluation {
+            outcome: Outcome::Draw,
+            plies,
+        }
+    }
+
+    /// Plain minimax over every move, without any pruning
+    fn brute_force<const W: usize, const H: usize>(
+        board: &mut Board<W, H>,
+        player: Player,
+    ) -> Evaluation {
+        let mut best = None;
+        for col in 0..W as Col {
+            let Ok(row) = board.play(player, col) else {
+                continue;
+            };
+            let evaluation = if board.check_win(row, col, player) {
+                win(1)
+            } else {
+                brute_force(board, player.opponent()).parent()
+            };
+            board.undo();
+            best = best.max(Some(evaluation));
+        }
+        best.unwrap_or(draw(0))
+    }
+
+    /// Random game of `ply` moves, `None` if it ends earlier
+    fn random_game<const W: usize, const H: usize>(
+        rng: &mut impl Rng,
+        win_length: usize,
+        ply: usize,
+    ) -> Option<(Board<W, H>, Player)> {
+        let mut board = Board::with_win_length(win_length);
+        let mut player = Player::Red;
+        for _ in 0..ply {
+            let col = rng.gen_range(0..W) as Col;
+            let row = board.play(player, col).ok()?;
+            if board.check_win(row, col, player) {
+                return None;
+            }
+            player = player.opponent();
+        }
+        Some((board, player))
+    }
+
+    fn compare_with_brute_force<const W: usize, const H: usize>(win_length: usize, ply: usize) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut solver = Solver::<W, H>::with_table_bits(16);
+        let mut checked = 0;
+        while checked < 30 {
+            let Some((mut board, player)) = random_game::<W, H>(&mut rng, win_length, ply) else {
+                continue;
+            };
+            let analysis = solver.analyze(&board);
+            assert_eq!(
+                analysis.evaluation,
+                brute_force(&mut board, player),
+                "{board}"
+            );
+            assert_eq!(
+                analysis.evaluation,
+                analysis.scores.iter().flatten().copied().max().unwrap()
+            );
+            checked += 1;
+        }
+    }
+
+    #[test]
+    fn center_first() {
+        assert_eq!(center_order::<7>(), [3, 2, 4, 1, 5, 0, 6]);
+        assert_eq!(center_order::<8>(), [4, 3, 5, 2, 6, 1, 7, 0]);
+        assert_eq!(center_order::<1>(), [0]);
+    }
+
+    #[test]
+    fn evaluation_order() {
+        assert!(win(1) > win(3));
+        assert!(win(41) > draw(10));
+        assert!(draw(10) > loss(20));
+        assert!(loss(20) > loss(2));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        compare_with_brute_force::<4, 4>(3, 4);
+        compare_with_brute_force::<4, 4>(4, 6);
+        compare_with_brute_force::<5, 4>(4, 11);
+        compare_with_brute_force::<4, 5>(3, 10);
+    }
+
+    #[test]
+    fn immediate_results() {
+        type SmallBoard = Board<5, 4>;
+        let mut solver = Solver::<5, 4>::with_table_bits(16);
+        let board = SmallBoard::from_moves("121212").unwrap();
+        let analysis = solver.analyze(&board);
+        assert_eq!(analysis.evaluation, win(1));
+        assert_eq!(analysis.best_move, Some(0));
+
+        // Yellow has to block, or loses right away
+        let board = SmallBoard::from_moves("12121").unwrap();
+        let analysis = solver.analyze(&board);
+        assert_eq!(analysis.scores[1], Some(loss(2)));
+        assert!(analysis.scores[0].unwrap() > loss(2));
+        assert_eq!(analysis.best_move, Some(0));
+
+        // Red threatens both ends of an open three
+        let board = SmallBoard::from_moves("22334").unwrap();
+        assert_eq!(solver.solve(&board), loss(2));
+
+        let board = SmallBoard::from_moves("1212121").unwrap();
+        assert_eq!(solver.solve(&board), loss(0));
+        assert_eq!(solver.analyze(&board).best_move, None);
+    }
+
+    #[test]
+    fn full_board_is_a_draw() {
+        let board: Board<4, 2> = "
+            |y|r|y|r|
+            |r|y|r|y|
+            "
+        .parse()
+        .unwrap();
+        let mut solver = Solver::<4, 2>::with_table_bits(8);
+        assert_eq!(solver.solve(&board), draw(0));
+        assert_eq!(solver.analyze(&board).best_move, None);
+    }
+
+    #[test]
+    fn mirrored_scores() {
+        let mut solver = Solver::<7, 6>::with_table_bits(20);
+        let board = StandardBoard::from_moves("4444443333355555").unwrap();
+        let mirror = StandardBoard::from_moves("4444445555533333").unwrap();
+        let mut scores = solver.analyze(&board).scores;
+        scores.reverse();
+        assert_eq!(solver.analyze(&mirror).scores, scores);
+    }
+
+    #[test]
+    #[ignore = "solves the whole game, takes a few minutes with --release"]
+    fn standard_opening() {
+        let mut solver = Solver::<7, 6>::new();
+        assert_eq!(solver.solve(&StandardBoard::default()), win(41));
+        // The first player loses by starting on the side of the board
+        assert_eq!(
+            solver.solve(&StandardBoard::from_moves("1").unwrap()),
+            win(39)
+        );
+    }
+}
@@ -0,0 +1,701 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Col, GamePlay, IllegalMove, ParseBoardError, TerminatedStatus},
+    player::Player,
+    HEIGHT, MAX_PLAYERS, WIDTH, WIN_LENGTH,
+};
+
+/// Reasons a deserialized game state is refused, as it could not be reached in a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidState {
+    /// Games are played by 2 to [`MAX_PLAYERS`] players
+    Players(usize),
+    /// A player who does not take part in the game has pieces or moves
+    Outsider(Player),
+    /// Players forfeited after the end of the game or later than the moves played
+    Forfeits,
+    /// The number of moves played does not match the history of the board
+    Ply { ply: usize, history: usize },
+    /// The players do not have the number of pieces taking turns would give them
+    PieceCounts,
+    /// Another player should be to move
+    ToMove { expected: Player, found: Player },
+    /// The recorded result does not match the board
+    Result(Option<TerminatedStatus>),
+    /// The positions seen so far do not match the moves played
+    Positions,
+    /// The board does not match the stage of the game
+    Phase,
+}
+
+impl Display for InvalidState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidState::Players(players) => {
+                write!(
+                    f,
+                    "Games are played by 2 to {MAX_PLAYERS} players, not {players}"
+                )
+            }
+            InvalidState::Outsider(player) => {
+                write!(f, "{player:?} does not take part in the game")
+            }
+            InvalidState::Forfeits => {
+                write!(f, "The forfeits do not match the players and moves played")
+            }
+            InvalidState::Ply { ply, history } => {
+                write!(f, "{ply} moves were played but the history holds {history}")
+            }
+            InvalidState::PieceCounts => {
+                write!(f, "The pieces could not have been played in turn")
+            }
+            InvalidState::ToMove { expected, found } => {
+                write!(f, "{expected:?} should be to move, not {found:?}")
+            }
+            InvalidState::Result(result) => {
+                write!(f, "The result {result:?} does not match the board")
+            }
+            InvalidState::Positions => {
+                write!(f, "The positions seen do not match the moves played")
+            }
+            InvalidState::Phase => write!(f, "The board does not match the stage of the game"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidState {}
+
+/// Checks that the game has a valid number of players,
+/// and that only the players taking part have pieces on the board
+pub(crate) fn check_players<const W: usize, const H: usize>(
+    board: &Board<W, H>,
+    players: usize,
+) -> Result<(), InvalidState> {
+    if !(2..=MAX_PLAYERS).contains(&players) {
+        return Err(InvalidState::Players(players));
+    }
+    match Player::ALL[players..]
+        .iter()
+        .find(|player| board.count(**player) > 0)
+    {
+        Some(outsider) => Err(InvalidState::Outsider(*outsider)),
+        None => Ok(()),
+    }
+}
+
+/// Game played on a [`Board`], keeping track of the player to move,
+/// the number of moves played and the result once the game is over.
+/// Red always moves first, the other players follow in the order of [`Player::ALL`].
+/// Players who forfeit leave the game and are skipped, the last one left wins.
+///
+/// Deserialized states are checked to be consistent with their board,
+/// games without a winning line can still have been won by forfeit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedGameState<W, H>")]
+pub struct GameState<const W: usize = WIDTH, const H: usize = HEIGHT> {
+    board: Board<W, H>,
+    /// Number of players taking turns
+    players: usize,
+    to_move: Player,
+    ply: usize,
+    result: Option<TerminatedStatus>,
+    /// Ply at which every player who left the game forfeited, indexed by [`Player`]
+    forfeits: [Option<usize>; MAX_PLAYERS],
+}
+
+/// Fields of a [`GameState`] as read, before they are checked
+#[derive(Deserialize)]
+struct UncheckedGameState<const W: usize, const H: usize> {
+    board: Board<W, H>,
+    players: usize,
+    to_move: Player,
+    ply: usize,
+    result: Option<TerminatedStatus>,
+    forfeits: [Option<usize>; MAX_PLAYERS],
+}
+
+impl<const W: usize, const H: usize> TryFrom<UncheckedGameState<W, H>> for GameState<W, H> {
+    type Error = InvalidState;
+
+    fn try_from(state: UncheckedGameState<W, H>) -> Result<Self, InvalidState> {
+        let UncheckedGameState {
+            board,
+            players,
+            to_move,
+            ply,
+            result,
+            forfeits,
+        } = state;
+        check_players(&board, players)?;
+        let history = board.history().len();
+        if ply != history {
+            return Err(InvalidState::Ply { ply, history });
+        }
+        let left = Player::ALL[..players]
+            .iter()
+            .copied()
+            .filter(|player| forfeits[*player as usize].is_none())
+            .collect::<Vec<_>>();
+        let forfeited = forfeits
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ply)| ply.map(|ply| (i, ply)));
+        if left.is_empty()
+            || forfeited
+                .clone()
+                .any(|(i, forfeit)| i >= players || forfeit > ply)
+        {
+            return Err(InvalidState::Forfeits);
+        }
+
+        // Replays the turns, the pieces already on the board when the game started
+        // being shared out in turn as well, and players leaving when they forfeited
+        let len = board.len();
+        let start = len
+            .checked_sub(ply)
+            .ok_or(InvalidState::Ply { ply, history })?;
+        let out = |player: Player, pieces: usize| {
+            forfeits[player as usize].is_some_and(|forfeit| start + forfeit <= pieces)
+        };
+        let mut counts = [0; MAX_PLAYERS];
+        let mut turn = Player::Red;
+        let mut last = None;
+        for pieces in 0..=len {
+            while out(turn, pieces) {
+                turn = turn.next(players);
+            }
+            if pieces == len {
+                break;
+            }
+            counts[turn as usize] += 1;
+            last = Some(turn);
+            turn = turn.next(players);
+        }
+        if Player::ALL[..players]
+            .iter()
+            .any(|player| board.count(*player) != counts[*player as usize])
+        {
+            return Err(InvalidState::PieceCounts);
+        }
+        if to_move != turn {
+            return Err(InvalidState::ToMove {
+                expected: turn,
+                found: to_move,
+            });
+        }
+
+        let full = board.is_full();
+        let winners = Player::ALL[..players]
+            .iter()
+            .filter(|player| board.has_won(**player))
+            .collect::<Vec<_>>();
+        let consistent = match (winners.as_slice(), result) {
+            // The last player left wins by forfeit, without a winning line
+            ([], Some(TerminatedStatus::Win(winner))) => left == [winner],
+            _ if left.len() == 1 => false,
+            ([], None) => !full,
+            ([], Some(TerminatedStatus::Draw)) => full,
+            ([&winner], Some(TerminatedStatus::Win(found))) => {
+                last == Some(winner) && found == winner
+            }
+            _ => false,
+        };
+        if !consistent {
+            return Err(InvalidState::Result(result));
+        }
+        // Nobody can forfeit once the game is over
+        if result.is_some()
+            && left.len() > 1
+            && forfeited.clone().any(|(_, forfeit)| forfeit == ply)
+        {
+            return Err(InvalidState::Forfeits);
+        }
+        Ok(Self {
+            board,
+            players,
+            to_move,
+            ply,
+            result,
+            forfeits,
+        })
+    }
+}
+
+/// Game on the board of the classic Connect Four
+pub type StandardGame = GameState<7, 6>;
+
+impl<const W: usize, const H: usize> Default for GameState<W, H> {
+    fn default() -> Self {
+        Self::with_win_length(WIN_LENGTH)
+    }
+}
+
+impl<const W: usize, const H: usize> GameState<W, H> {
+    /// New two-player game where `win_length` pieces in a row are needed to win
+    pub fn with_win_length(win_length: usize) -> Self {
+        Self::with_players(2, win_length)
+    }
+
+    /// New game between `players` players,
+    /// where `win_length` pieces in a row are needed to win
+    ///
+    /// Panics unless there are between 2 and [`MAX_PLAYERS`] players
+    pub fn with_players(players: usize, win_length: usize) -> Self {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&players),
+            "Games are played by 2 to {MAX_PLAYERS} players, not {players}"
+        );
+        Self {
+            board: Board::with_win_length(win_length),
+            players,
+            to_move: Player::Red,
+            ply: 0,
+            result: None,
+            forfeits: [None; MAX_PLAYERS],
+        }
+    }
+
+    /// Game after the moves in `moves`, as read by [`Board::from_moves`]
+    pub fn from_moves(moves: &str) -> Result<Self, ParseBoardError> {
+        let mut state = Self::default();
+        for col in Board::<W, H>::from_moves(moves)?.history() {
+            state.apply(state.to_move, *col);
+        }
+        Ok(state)
+    }
+
+    /// Two-player game going on from `board`, with moves counted from there.
+    /// Red is to move unless it has more pieces than yellow
+    pub(crate) fn from_board(board: Board<W, H>) -> Self {
+        let to_move = if board.count(Player::Red) > board.count(Player::Yellow) {
+            Player::Yellow
+        } else {
+            Player::Red
+        };
+        let result = if board.has_won(to_move.opponent()) {
+            Some(TerminatedStatus::Win(to_move.opponent()))
+        } else if board.is_full() {
+            Some(TerminatedStatus::Draw)
+        } else {
+            None
+        };
+        Self {
+            board,
+            players: 2,
+            to_move,
+            ply: 0,
+            result,
+            forfeits: [None; MAX_PLAYERS],
+        }
+    }
+
+    pub fn board(&self) -> &Board<W, H> {
+        &self.board
+    }
+
+    /// Number of players taking turns
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Player expected to play the next move
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Number of moves played so far
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Outcome of the game, `None` while it is still going on
+    pub fn result(&self) -> Option<TerminatedStatus> {
+        self.result
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Whether `player` takes part in the game and has not forfeited
+    pub fn in_game(&self, player: Player) -> bool {
+        (player as usize) < self.players && self.forfeits[player as usize].is_none()
+    }
+
+    /// Next player after `player` in turn order who is still in the game
+    fn next_in_game(&self, player: Player) -> Player {
+        let mut next = player.next(self.players);
+        while !self.in_game(next) && next != player {
+            next = next.next(self.players);
+        }
+        next
+    }
+
+    /// Plays `col` for `player`.
+    /// Moves out of turn or after the end of the game are refused
+    /// and leave the state untouched
+    pub fn apply(&mut self, player: Player, col: Col) -> GamePlay {
+        if self.is_terminated() {
+            return GamePlay::InvalidBoard(IllegalMove::GameOver);
+        }
+        if player != self.to_move {
+            return GamePlay::InvalidBoard(IllegalMove::WrongPlayer);
+        }
+        let row = match self.board.play(player, col) {
+            Ok(row) => row,
+            Err(err) => return GamePlay::InvalidBoard(err),
+        };
+
+        self.ply += 1;
+        self.to_move = self.next_in_game(player);
+        self.result = if self.board.check_win(row, col, player) {
+            Some(TerminatedStatus::Win(player))
+        } else if self.board.is_full() {
+            Some(TerminatedStatus::Draw)
+        } else {
+            None
+        };
+        match self.result {
+            Some(status) => GamePlay::GameTerminated(status),
+            None => GamePlay::ValidPlay,
+        }
+    }
+
+    /// Takes `player` out of the game, the others playing on without it.
+    /// The last player left wins the game, so a forfeit ends a two-player game.
+    /// Returns the result once the game is over, `None` while it goes on
+    pub fn forfeit(&mut self, player: Player) -> Option<TerminatedStatus> {
+        if self.is_terminated() || !self.in_game(player) {
+            return self.result;
+        }
+        self.forfeits[player as usize] = Some(self.ply);
+        if self.to_move == player {
+            self.to_move = self.next_in_game(player);
+        }
+        let mut left = Player::ALL[..self.players]
+            .iter()
+            .filter(|player| self.in_game(**player));
+        if let (Some(winner), None) = (left.next(), left.next()) {
+            self.result = Some(TerminatedStatus::Win(*winner));
+        }
+        self.result
+    }
+
+    /// Takes back the last move, returning its column.
+    /// Forfeits made since that move are taken back with it
+    pub fn undo(&mut self) -> Option<Col> {
+        let before = self.board;
+        let col = self.board.undo()?;
+        self.ply -= 1;
+        for forfeit in &mut self.forfeits {
+            if forfeit.is_some_and(|ply| ply > self.ply) {
+                *forfeit = None;
+            }
+        }
+        self.to_move = Player::ALL[..self.players]
+            .iter()
+            .copied()
+            .find(|player| before.pieces(*player) != self.board.pieces(*player))
+            .expect("The piece taken back belongs to a player");
+        self.result = None;
+        Some(col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_all(state: &mut StandardGame, moves: &[Col]) -> GamePlay {
+        moves.iter().fold(GamePlay::ValidPlay, |_, col| {
+            state.apply(state.to_move(), *col)
+        })
+    }
+
+    #[test]
+    fn new_game() {
+        let state = StandardGame::default();
+        assert_eq!(state.to_move(), Player::Red);
+        assert_eq!(state.ply(), 0);
+        assert_eq!(state.result(), None);
+        assert_eq!(state.board().win_length(), WIN_LENGTH);
+    }
+
+    #[test]
+    fn players_take_turns() {
+        let mut state = StandardGame::default();
+        assert_eq!(state.apply(Player::Red, 3), GamePlay::ValidPlay);
+        assert_eq!(state.to_move(), Player::Yellow);
+        assert_eq!(
+            state.apply(Player::Red, 3),
+            GamePlay::InvalidBoard(IllegalMove::WrongPlayer)
+        );
+        assert_eq!(state.ply(), 1);
+        assert_eq!(state.apply(Player::Yellow, 3), GamePlay::ValidPlay);
+        assert_eq!(state.to_move(), Player::Red);
+        assert_eq!(state.ply(), 2);
+    }
+
+    #[test]
+    fn games_from_moves() {
+        let state = StandardGame::from_moves("4455").unwrap();
+        assert_eq!(state.board().history(), &[3, 3, 4, 4]);
+        assert_eq!(state.to_move(), Player::Red);
+        assert_eq!(state.ply(), 4);
+
+        let state = StandardGame::from_moves("1212121").unwrap();
+        assert_eq!(state.result(), Some(TerminatedStatus::Win(Player::Red)));
+        assert!(StandardGame::from_moves("8").is_err());
+    }
+
+    #[test]
+    fn illegal_moves_keep_the_turn() {
+        let mut state = StandardGame::default();
+        play_all(&mut state, &[0, 0, 0, 0, 0, 0]);
+        let before = state;
+        assert_eq!(
+            state.apply(Player::Red, 0),
+            GamePlay::InvalidBoard(IllegalMove::StackIsFull)
+        );
+        assert_eq!(
+            state.apply(Player::Red, 7),
+            GamePlay::InvalidBoard(IllegalMove::OutOfBounds)
+        );
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn win_ends_the_game() {
+        let mut state = StandardGame::default();
+        assert_eq!(
+            play_all(&mut state, &[0, 1, 0, 1, 0, 1, 0]),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert!(state.is_terminated());
+        assert_eq!(
+            state.apply(Player::Yellow, 1),
+            GamePlay::InvalidBoard(IllegalMove::GameOver)
+        );
+        assert_eq!(state.ply(), 7);
+
+        assert_eq!(state.undo(), Some(0));
+        assert_eq!(state.result(), None);
+        assert_eq!(state.to_move(), Player::Red);
+        assert_eq!(state.apply(Player::Red, 2), GamePlay::ValidPlay);
+    }
+
+    #[test]
+    fn connect_3_win() {
+        let mut state = GameState::<7, 6>::with_win_length(3);
+        assert_eq!(
+            play_all(&mut state, &[0, 0, 1, 1, 2]),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+    }
+
+    #[test]
+    fn three_players() {
+        let mut state = GameState::<9, 6>::with_players(3, 4);
+        assert_eq!(state.players(), 3);
+        assert_eq!(state.apply(Player::Red, 0), GamePlay::ValidPlay);
+        assert_eq!(state.apply(Player::Yellow, 1), GamePlay::ValidPlay);
+        assert_eq!(
+            state.apply(Player::Red, 2),
+            GamePlay::InvalidBoard(IllegalMove::WrongPlayer)
+        );
+        assert_eq!(state.apply(Player::Green, 2), GamePlay::ValidPlay);
+        assert_eq!(state.to_move(), Player::Red);
+
+        assert_eq!(state.undo(), Some(2));
+        assert_eq!(state.to_move(), Player::Green);
+        state.apply(Player::Green, 2);
+        for _ in 0..2 {
+            for col in 0..3 {
+                state.apply(state.to_move(), col);
+            }
+        }
+        assert_eq!(
+            state.apply(Player::Red, 0),
+            GamePlay::GameTerminated(TerminatedStatus::Win(Player::Red))
+        );
+        assert_eq!(state.ply(), 10);
+    }
+
+    #[test]
+    fn four_players_fill_the_board() {
+        let mut state = GameState::<4, 4>::with_players(4, 4);
+        // Every row holds one piece of each player and no line is of a single colour
+        let moves = [0, 1, 2, 3, 2, 3, 0, 1, 0, 1, 2, 3, 2, 3, 0, 1];
+        for (ply, col) in moves.into_iter().enumerate() {
+            assert_eq!(state.to_move(), Player::ALL[ply % 4]);
+            let expected = if ply == 15 {
+                GamePlay::GameTerminated(TerminatedStatus::Draw)
+            } else {
+                GamePlay::ValidPlay
+            };
+            assert_eq!(state.apply(state.to_move(), col), expected);
+        }
+    }
+
+    #[test]
+    fn forfeits_take_players_out() {
+        let mut state = GameState::<9, 6>::with_players(3, 4);
+        state.apply(Player::Red, 0);
+        state.apply(Player::Yellow, 1);
+        assert_eq!(state.forfeit(Player::Green), None);
+        assert!(!state.in_game(Player::Green));
+        assert_eq!(state.to_move(), Player::Red);
+        state.apply(Player::Red, 0);
+        assert_eq!(state.to_move(), Player::Yellow);
+        state.apply(Player::Yellow, 1);
+        assert_eq!(state.forfeit(Player::Green), None);
+
+        assert_eq!(
+            state.forfeit(Player::Yellow),
+            Some(TerminatedStatus::Win(Player::Red))
+        );
+        assert_eq!(
+            state.apply(Player::Red, 0),
+            GamePlay::InvalidBoard(IllegalMove::GameOver)
+        );
+        // Taking back a move also takes back the forfeits made since
+        assert_eq!(state.undo(), Some(1));
+        assert_eq!(state.result(), None);
+        assert_eq!(state.to_move(), Player::Yellow);
+        assert!(state.in_game(Player::Yellow));
+        assert!(!state.in_game(Player::Green));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_players() {
+        StandardGame::with_players(5, 4);
+    }
+
+    #[test]
+    fn full_board_draw() {
+        let mut state = GameState::<4, 4>::default();
+        let moves = [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 3, 3, 3, 2];
+        for col in moves {
+            assert_eq!(state.apply(state.to_move(), col), GamePlay::ValidPlay);
+        }
+        assert_eq!(
+            state.apply(Player::Yellow, 3),
+            GamePlay::GameTerminated(TerminatedStatus::Draw)
+        );
+        assert_eq!(state.ply(), 16);
+        assert_eq!(
+            state.apply(Player::Red, 2),
+            GamePlay::InvalidBoard(IllegalMove::GameOver)
+        );
+    }
+
+    /// Serialized form of the state, with some of its fields replaced
+    fn tampered<const W: usize, const H: usize>(
+        state: &GameState<W, H>,
+        fields: &[(&str, serde_json::Value)],
+    ) -> String {
+        let mut json = serde_json::to_value(state).unwrap();
+        for (field, value) in fields {
+            json[*field] = value.clone();
+        }
+        json.to_string()
+    }
+
+    fn rejection(json: &str) -> String {
+        serde_json::from_str::<StandardGame>(json)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn deserialized_states_are_checked() {
+        let mut state = StandardGame::default();
+        play_all(&mut state, &[3, 3, 4]);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<StandardGame>(&json).unwrap(), state);
+
+        let json = tampered(&state, &[("ply", 0.into())]);
+        assert!(rejection(&json).contains("history holds 3"));
+        let json = tampered(&state, &[("to_move", "Red".into())]);
+        assert!(rejection(&json).contains("Yellow should be to move"));
+        let json = tampered(&state, &[("result", serde_json::json!("Draw"))]);
+        assert!(rejection(&json).contains("does not match the board"));
+
+        // Yellow can not be ahead of red
+        let mut board = serde_json::to_value(state.board()).unwrap();
+        board["columns"][3] = "yy".into();
+        let json = tampered(&state, &[("board", board)]);
+        assert!(rejection(&json).contains("in turn"));
+    }
+
+    #[test]
+    fn deserialized_players_are_checked() {
+        let mut state = StandardGame::default();
+        play_all(&mut state, &[3, 3, 4]);
+        let json = tampered(&state, &[("players", 5.into())]);
+        assert!(rejection(&json).contains("not 5"));
+        let json = tampered(&state, &[("players", 3.into())]);
+        assert!(rejection(&json).contains("in turn"));
+        let mut board = serde_json::to_value(state.board()).unwrap();
+        board["columns"][0] = "g".into();
+        let json = tampered(&state, &[("board", board)]);
+        assert!(rejection(&json).contains("Green does not take part"));
+
+        let mut state = GameState::<9, 6>::with_players(3, 4);
+        state.apply(Player::Red, 0);
+        state.apply(Player::Yellow, 1);
+        state.forfeit(Player::Green);
+        state.apply(Player::Red, 0);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            serde_json::from_str::<GameState<9, 6>>(&json).unwrap(),
+            state
+        );
+        let rejection = |json: &str| {
+            serde_json::from_str::<GameState<9, 6>>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        let json = tampered(
+            &state,
+            &[("forfeits", serde_json::json!([null, null, 4, null]))],
+        );
+        assert!(rejection(&json).contains("forfeits"));
+        let json = tampered(
+            &state,
+            &[("forfeits", serde_json::json!([null, null, 2, 0]))],
+        );
+        assert!(rejection(&json).contains("forfeits"));
+        let json = tampered(
+            &state,
+            &[("forfeits", serde_json::json!([null, null, null, null]))],
+        );
+        assert!(rejection(&json).contains("in turn"));
+    }
+
+    #[test]
+    fn deserialized_results_match_the_board() {
+        let mut state = StandardGame::default();
+        play_all(&mut state, &[0, 1, 0, 1, 0, 1, 0]);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<StandardGame>(&json).unwrap(), state);
+        let json = tampered(&state, &[("result", serde_json::Value::Null)]);
+        assert!(rejection(&json).contains("does not match the board"));
+        let json = tampered(
+            &state,
+            &[("result", serde_json::json!({ "Win": "Yellow" }))],
+        );
+        assert!(rejection(&json).contains("does not match the board"));
+
+        // Forfeits leave no line on the board
+        let mut state = StandardGame::default();
+        play_all(&mut state, &[3]);
+        state.forfeit(Player::Yellow);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<StandardGame>(&json).unwrap(), state);
+    }
+}